@@ -1,42 +1,629 @@
 use crate::serial::SerialManager;
 use gloo_net::http::Request;
-use gloo_timers::callback::Interval;
-use serde::Deserialize;
+use gloo_timers::callback::{Interval, Timeout};
+use gloo_timers::future::TimeoutFuture;
+use serde::{Deserialize, Serialize};
 use yew::prelude::*;
 use yew::events::InputEvent;
-use web_sys::{HtmlInputElement, Storage};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
 use wasm_bindgen_futures::spawn_local;
-use crate::serial::KenwoodDriver;
-use wasm_bindgen::JsValue;
+use crate::serial::{filter_width_commands, AgcSpeed, KenwoodModel, normalize_raw_command, parse_cat_sequence, KenwoodDriver, DEFAULT_COMMAND_DELAY_MS, KEYER_MAX_WPM, KEYER_MIN_WPM, RIT_MAX_HZ, RigDriver, SessionStats, SplitState, YaesuDriver, DEFAULT_FRAME_TIMEOUT_MS, POWER_ON_TIMEOUT_MS};
+use crate::settings::{self, Kind};
+use crate::status::{use_status, StatusHandle, StatusLevel, Toasts};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
-const SPOTS_URL: &str = "https://api2.sota.org.uk/api/spots/20/%7Bfilter%7D?filter=all";
-const REFRESH_MS: u32 = 5 * 60 * 1000;
+const DEFAULT_SPOTS_API: &str = "https://api2.sota.org.uk/api/spots/";
+const DEFAULT_SPOT_COUNT: u32 = 20;
+const SUMMIT_URL: &str = "https://api-db2.sota.org.uk/api/summits/";
+/// Automatic refresh interval bounds and default, in seconds.
+const DEFAULT_REFRESH_SECS: u32 = 5 * 60;
+const MIN_REFRESH_SECS: u32 = 30;
+const MAX_REFRESH_SECS: u32 = 3600;
+const STORAGE_REFRESH_SECS: &str = "sotachaser.refresh_secs";
+/// Delays before retrying a failed fetch; after the last one we fall back
+/// to the normal refresh interval.
+const RETRY_DELAYS_MS: &[u32] = &[5_000, 15_000, 45_000];
 const STORAGE_MIN_FREQ: &str = "sotachaser.min_freq_mhz";
 const STORAGE_MAX_FREQ: &str = "sotachaser.max_freq_mhz";
+const STORAGE_SPOT_COUNT: &str = "sotachaser.spot_count";
+const STORAGE_SPOTS_API: &str = "sotachaser.spots_api";
+const STORAGE_BAND: &str = "sotachaser.band";
+const STORAGE_MODES: &str = "sotachaser.modes";
+const STORAGE_REGIONS: &str = "sotachaser.regions";
+const STORAGE_ASSOCIATIONS: &str = "sotachaser.associations";
+const STORAGE_COMMENT_FILTER: &str = "sotachaser.comment_filter";
+const STORAGE_FAVORITES: &str = "sotachaser.favorites";
+const STORAGE_FAVORITES_ONLY: &str = "sotachaser.favorites_only";
+const STORAGE_COMPACT: &str = "sotachaser.compact";
+const STORAGE_COMPACT_HIDE_COMMENTS: &str = "sotachaser.compact_hide_comments";
+const STORAGE_HIDDEN_COLUMNS: &str = "sotachaser.hidden_columns";
+const STORAGE_WORKED: &str = "sotachaser.worked";
+const STORAGE_BAND_MODES: &str = "sotachaser.band_modes";
+const STORAGE_BAND_MODE_MEMORY: &str = "sotachaser.band_mode_memory";
+const STORAGE_SPOTS_CACHE: &str = "sotachaser.spots_cache";
+const STORAGE_DEDUP_MINUTES: &str = "sotachaser.dedup_minutes";
+const DEFAULT_DEDUP_MINUTES: f64 = 10.0;
+const STORAGE_BAUD_RATE: &str = "sotachaser.baud_rate";
+const DEFAULT_BAUD_RATE: u32 = 9600;
+const BAUD_RATES: &[u32] = &[4800, 9600, 19200, 38400, 57600, 115200];
+const STORAGE_RIG: &str = "sotachaser.rig";
+const STORAGE_KENWOOD_MODEL: &str = "sotachaser.kenwood_model";
+const STORAGE_SPLIT: &str = "sotachaser.split_enabled";
+const STORAGE_VERIFY_TUNE: &str = "sotachaser.verify_tune";
+const STORAGE_TUNE_FILTER: &str = "sotachaser.tune_filter";
+const STORAGE_CW_WIDTH: &str = "sotachaser.cw_width_hz";
+const STORAGE_SSB_WIDTH: &str = "sotachaser.ssb_width_hz";
+const DEFAULT_CW_WIDTH_HZ: u32 = 500;
+const DEFAULT_SSB_WIDTH_HZ: u32 = 2400;
+const STORAGE_CAT_MACRO: &str = "sotachaser.cat_macro";
+/// How long to collect replies after a macro before logging them.
+const MACRO_REPLY_MS: u32 = 300;
+/// How long the manual "Read" button waits for a frame.
+const READ_ONCE_MS: u32 = 1_000;
+const STORAGE_DIGITAL_DIAL: &str = "sotachaser.digital_dial";
+/// Allowed difference between the requested and read-back frequency.
+const VERIFY_TOLERANCE_HZ: u64 = 10;
+const STORAGE_WATCHLIST: &str = "sotachaser.watchlist";
+const STORAGE_MY_SUMMIT: &str = "sotachaser.my_summit";
+const STORAGE_MY_GRID: &str = "sotachaser.my_grid";
+const STORAGE_S2S_ONLY: &str = "sotachaser.s2s_only";
+const STORAGE_WATCH_BEEP: &str = "sotachaser.watch_beep";
+const STORAGE_ALERT_MUTED: &str = "sotachaser.alert_muted";
+const STORAGE_LOG_TX: &str = "sotachaser.log_tx";
+const STORAGE_COMMAND_DELAY: &str = "sotachaser.command_delay_ms";
+const MIN_COMMAND_DELAY_MS: u32 = 10;
+const MAX_COMMAND_DELAY_MS: u32 = 1000;
+const STORAGE_SESSION_MODE: &str = "sotachaser.session_mode";
+const STORAGE_STALE_MINUTES: &str = "sotachaser.stale_minutes";
+const DEFAULT_STALE_MINUTES: f64 = 30.0;
+const STORAGE_CLASH_KHZ: &str = "sotachaser.clash_khz";
+const DEFAULT_CLASH_KHZ: f64 = 0.5;
+const STORAGE_POLL_TX: &str = "sotachaser.poll_tx";
+/// How often the TX/RX state is polled while the on-air indicator is on.
+const TX_POLL_MS: u32 = 1_000;
+const STORAGE_COPY_DETAILS: &str = "sotachaser.copy_details";
+const STORAGE_SHOW_MAP: &str = "sotachaser.show_map";
+const STORAGE_MAP_TUNES: &str = "sotachaser.map_tunes";
+const STORAGE_SEGMENT_RULES: &str = "sotachaser.segment_rules";
+const STORAGE_TUNING_LOCKED: &str = "sotachaser.tuning_locked";
+const TUNING_LOCKED: &str = "Tuning is locked";
+/// Shown when a command that can key the radio is tried with TX off.
+const TX_DISABLED: &str = "TX is disabled; turn on TX to allow commands that can transmit";
+/// Set once the first-run tip on the settings gear has been seen.
+const STORAGE_ONBOARDED: &str = "sotachaser.onboarded";
+/// Keys accepted by "Import settings". The spots cache is left out on
+/// purpose: it is stale by the time anyone imports it.
+const SETTINGS_SCHEMA: &[(&str, Kind)] = &[
+    (STORAGE_REFRESH_SECS, Kind::Number),
+    (STORAGE_MIN_FREQ, Kind::Number),
+    (STORAGE_MAX_FREQ, Kind::Number),
+    (STORAGE_SPOT_COUNT, Kind::Number),
+    (STORAGE_SPOTS_API, Kind::Text),
+    (STORAGE_BAND, Kind::Text),
+    (STORAGE_MODES, Kind::Text),
+    (STORAGE_REGIONS, Kind::Text),
+    (STORAGE_ASSOCIATIONS, Kind::Text),
+    (STORAGE_COMMENT_FILTER, Kind::Text),
+    (STORAGE_FAVORITES, Kind::Json),
+    (STORAGE_FAVORITES_ONLY, Kind::Bool),
+    (STORAGE_COMPACT, Kind::Bool),
+    (STORAGE_COMPACT_HIDE_COMMENTS, Kind::Bool),
+    (STORAGE_HIDDEN_COLUMNS, Kind::Json),
+    (STORAGE_WORKED, Kind::Json),
+    (STORAGE_BAND_MODES, Kind::Json),
+    (STORAGE_BAND_MODE_MEMORY, Kind::Bool),
+    (STORAGE_DEDUP_MINUTES, Kind::Number),
+    (STORAGE_BAUD_RATE, Kind::Number),
+    (STORAGE_RIG, Kind::Text),
+    (STORAGE_KENWOOD_MODEL, Kind::Text),
+    (STORAGE_SPLIT, Kind::Bool),
+    (STORAGE_VERIFY_TUNE, Kind::Bool),
+    (STORAGE_TUNE_FILTER, Kind::Bool),
+    (STORAGE_CW_WIDTH, Kind::Number),
+    (STORAGE_SSB_WIDTH, Kind::Number),
+    (STORAGE_CAT_MACRO, Kind::Text),
+    (STORAGE_DIGITAL_DIAL, Kind::Bool),
+    (STORAGE_WATCHLIST, Kind::Text),
+    (STORAGE_MY_SUMMIT, Kind::Text),
+    (STORAGE_MY_GRID, Kind::Text),
+    (STORAGE_S2S_ONLY, Kind::Bool),
+    (STORAGE_WATCH_BEEP, Kind::Bool),
+    (STORAGE_ALERT_MUTED, Kind::Bool),
+    (STORAGE_LOG_TX, Kind::Bool),
+    (STORAGE_COMMAND_DELAY, Kind::Number),
+    (STORAGE_SESSION_MODE, Kind::Bool),
+    (STORAGE_STALE_MINUTES, Kind::Number),
+    (STORAGE_CLASH_KHZ, Kind::Number),
+    (STORAGE_POLL_TX, Kind::Bool),
+    (STORAGE_COPY_DETAILS, Kind::Bool),
+    (STORAGE_SHOW_MAP, Kind::Bool),
+    (STORAGE_MAP_TUNES, Kind::Bool),
+    (STORAGE_SEGMENT_RULES, Kind::Text),
+    (STORAGE_TUNING_LOCKED, Kind::Bool),
+    (STORAGE_ONBOARDED, Kind::Bool),
+];
 
-fn get_storage() -> Option<Storage> {
-    web_sys::window().and_then(|w| w.local_storage().ok().flatten())
+/// Mode groups offered as filter toggles in the header.
+const MODE_GROUPS: &[&str] = &["CW", "SSB", "FT8", "FM", "Other"];
+
+/// Amateur band edges in MHz, used to filter the spot table by band.
+const BANDS: &[(&str, f64, f64)] = &[
+    ("160m", 1.8, 2.0),
+    ("80m", 3.5, 4.0),
+    ("40m", 7.0, 7.3),
+    ("30m", 10.1, 10.15),
+    ("20m", 14.0, 14.35),
+    ("17m", 18.068, 18.168),
+    ("15m", 21.0, 21.45),
+    ("12m", 24.89, 24.99),
+    ("10m", 28.0, 29.7),
+    ("6m", 50.0, 54.0),
+];
+
+fn load_baud_rate() -> u32 {
+    settings::get::<u32>(STORAGE_BAUD_RATE)
+        .filter(|b| BAUD_RATES.contains(b))
+        .unwrap_or(DEFAULT_BAUD_RATE)
 }
 
-fn load_freq(key: &str, default_value: f64) -> f64 {
-    if let Some(storage) = get_storage() {
-        if let Ok(Some(value)) = storage.get_item(key) {
-            if let Ok(parsed) = value.parse::<f64>() {
-                return parsed;
-            }
+/// Build the spots endpoint for `count` spots from an API base such as
+/// `https://api2.sota.org.uk/api/spots/`.
+fn spots_url(api_base: &str, count: u32) -> String {
+    let base = api_base.trim().trim_end_matches('/');
+    format!("{}/{}/%7Bfilter%7D?filter=all", base, count)
+}
+
+/// The API's change marker: it returns a new value whenever a spot is
+/// added, so an unchanged epoch means there is nothing new to fetch.
+fn epoch_url(api_base: &str) -> String {
+    format!("{}/epoch", api_base.trim().trim_end_matches('/'))
+}
+
+/// Modes the spots API accepts for a new spot, sent lowercased.
+const SELF_SPOT_MODES: &[&str] = &["CW", "SSB", "FM", "AM", "DATA", "OTHER"];
+/// Longest comment SOTAwatch shows in full.
+const MAX_SPOT_COMMENT: usize = 80;
+
+/// A spot to post, in the API's field names. Summit codes are split into
+/// association and summit the way the API stores them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct SelfSpot {
+    #[serde(rename = "associationCode")]
+    association: String,
+    #[serde(rename = "summitCode")]
+    summit: String,
+    #[serde(rename = "activatorCallsign")]
+    callsign: String,
+    frequency: String,
+    mode: String,
+    comments: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+}
+
+/// The self-spot form as typed.
+#[derive(Debug, Clone, PartialEq)]
+struct SelfSpotForm {
+    callsign: String,
+    summit: String,
+    frequency: String,
+    mode: String,
+    comments: String,
+}
+
+impl Default for SelfSpotForm {
+    fn default() -> Self {
+        Self {
+            callsign: String::new(),
+            summit: String::new(),
+            frequency: String::new(),
+            mode: SELF_SPOT_MODES[0].to_string(),
+            comments: String::new(),
+        }
+    }
+}
+
+impl SelfSpotForm {
+    /// Check every field before anything is sent, naming the first one
+    /// that's wrong.
+    fn validate(&self) -> Result<SelfSpot, String> {
+        let callsign = self.callsign.trim().to_uppercase();
+        if callsign.is_empty()
+            || !callsign.chars().all(|c| c.is_ascii_alphanumeric() || c == '/')
+            || !callsign.chars().any(|c| c.is_ascii_digit())
+        {
+            return Err("Enter a valid callsign".to_string());
+        }
+        let code = self.summit.trim().to_uppercase();
+        let (association, summit) = code
+            .split_once('/')
+            .filter(|(association, summit)| {
+                !association.is_empty()
+                    && association.chars().all(|c| c.is_ascii_alphanumeric())
+                    && summit.split_once('-').is_some_and(|(region, number)| {
+                        !region.is_empty()
+                            && region.chars().all(|c| c.is_ascii_alphanumeric())
+                            && !number.is_empty()
+                            && number.chars().all(|c| c.is_ascii_digit())
+                    })
+            })
+            .ok_or("Enter a summit code like W7W/LC-001")?;
+        let frequency_mhz = parse_decimal(&self.frequency)
+            .filter(|mhz| band_for_freq(*mhz).is_some())
+            .ok_or("Enter a frequency in MHz inside an amateur band")?;
+        let mode = SELF_SPOT_MODES
+            .iter()
+            .find(|m| m.eq_ignore_ascii_case(self.mode.trim()))
+            .ok_or("Pick a mode")?;
+        let comments = self.comments.trim();
+        if comments.chars().count() > MAX_SPOT_COMMENT {
+            return Err(format!("Keep the comment under {} characters", MAX_SPOT_COMMENT));
+        }
+        Ok(SelfSpot {
+            association: association.to_string(),
+            summit: summit.to_string(),
+            callsign,
+            frequency: format!("{:.4}", frequency_mhz),
+            mode: mode.to_lowercase(),
+            comments: comments.to_string(),
+            kind: "NORMAL",
+        })
+    }
+}
+
+/// Starred summit codes, stored as a JSON array.
+fn load_favorites() -> Vec<String> {
+    settings::get::<String>(STORAGE_FAVORITES)
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_favorites(favorites: &[String]) {
+    if let Ok(json) = serde_json::to_string(favorites) {
+        settings::set(STORAGE_FAVORITES, &json);
+    }
+}
+
+/// Hidden spot table columns, stored as a JSON array of column keys so
+/// columns added later show up by default.
+fn load_hidden_columns() -> Vec<Column> {
+    settings::get::<String>(STORAGE_HIDDEN_COLUMNS)
+        .and_then(|json| serde_json::from_str::<Vec<String>>(&json).ok())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|key| Column::from_key(key))
+        .collect()
+}
+
+fn save_hidden_columns(hidden: &[Column]) {
+    let keys: Vec<&str> = hidden.iter().map(|column| column.key()).collect();
+    if let Ok(json) = serde_json::to_string(&keys) {
+        settings::set(STORAGE_HIDDEN_COLUMNS, &json);
+    }
+}
+
+/// Worked (callsign, summit, band) keys, stored as a JSON array.
+fn load_worked() -> Vec<String> {
+    settings::get::<String>(STORAGE_WORKED)
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_worked(worked: &[String]) {
+    if let Ok(json) = serde_json::to_string(worked) {
+        settings::set(STORAGE_WORKED, &json);
+    }
+}
+
+/// Last mode used on each band, keyed by band label.
+fn load_band_modes() -> HashMap<String, String> {
+    settings::get::<String>(STORAGE_BAND_MODES)
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Record `mode` as the last one used on the band containing `mhz`.
+fn remember_band_mode(mhz: f64, mode: &str) {
+    let Some(band) = band_for_freq(mhz) else {
+        return;
+    };
+    let mut modes = load_band_modes();
+    modes.insert(band.to_string(), mode.to_uppercase());
+    if let Ok(json) = serde_json::to_string(&modes) {
+        settings::set(STORAGE_BAND_MODES, &json);
+    }
+}
+
+fn load_command_delay() -> u32 {
+    (settings::get_or(STORAGE_COMMAND_DELAY, DEFAULT_COMMAND_DELAY_MS as f64).round() as u32)
+        .clamp(MIN_COMMAND_DELAY_MS, MAX_COMMAND_DELAY_MS)
+}
+
+fn load_refresh_secs() -> u32 {
+    (settings::get_or(STORAGE_REFRESH_SECS, DEFAULT_REFRESH_SECS as f64).round() as u32)
+        .clamp(MIN_REFRESH_SECS, MAX_REFRESH_SECS)
+}
+
+fn load_spot_count() -> u32 {
+    settings::get::<u32>(STORAGE_SPOT_COUNT)
+        .filter(|c| *c > 0)
+        .unwrap_or(DEFAULT_SPOT_COUNT)
+}
+
+fn load_spots_api() -> String {
+    settings::get::<String>(STORAGE_SPOTS_API)
+        .filter(|v| v.starts_with("https://") || v.starts_with("http://"))
+        .unwrap_or_else(|| DEFAULT_SPOTS_API.to_string())
+}
+
+/// How long a frequency input must sit still before it's persisted.
+const SAVE_DEBOUNCE_MS: u32 = 500;
+
+/// Persist `value` once typing settles. Replacing the pending timeout drops
+/// it, which cancels the earlier save.
+fn debounce_save(pending: &Rc<RefCell<Option<Timeout>>>, key: &'static str, value: f64) {
+    *pending.borrow_mut() = Some(Timeout::new(SAVE_DEBOUNCE_MS, move || settings::set(key, value)));
+}
+
+/// Band label for a frequency in MHz, or `None` outside the ham bands.
+fn band_for_freq(mhz: f64) -> Option<&'static str> {
+    BANDS
+        .iter()
+        .find(|(_, lo, hi)| mhz >= *lo && mhz <= *hi)
+        .map(|(label, _, _)| *label)
+}
+
+fn in_band(band: &str, mhz: f64) -> bool {
+    band_for_freq(mhz) == Some(band)
+}
+
+/// SOTA association prefixes grouped by continent. A summit's association
+/// belongs to the group with the longest matching prefix, so `EA8` (Canary
+/// Islands) lands in Africa while `EA1` stays in Europe.
+const REGIONS: &[(&str, &[&str])] = &[
+    (
+        "North America",
+        &["W", "K", "VE", "VO", "VY", "XE", "TI", "HR", "HP", "TG", "YN", "CO", "KP4", "V3"],
+    ),
+    ("South America", &["CE", "CX", "LU", "PY", "CP", "HK", "HC", "OA", "YV", "PJ"]),
+    (
+        "Europe",
+        &[
+            "G", "M", "EI", "F", "DL", "DM", "OE", "HB", "I", "IS0", "EA", "CT", "LA", "SM", "OH",
+            "OZ", "OY", "TF", "ON", "PA", "LX", "OK", "OM", "SP", "S5", "9A", "E7", "YU", "Z3",
+            "LZ", "YO", "HA", "SV", "9H", "TK", "ES", "YL", "LY", "UR", "UT", "ER", "4O", "ZA",
+            "R", "UA", "3A",
+        ],
+    ),
+    (
+        "Asia",
+        &["JA", "BV", "HL", "DS", "VR", "VU", "4X", "TA", "A6", "9M", "HS", "R0", "R9", "UA9", "UA0", "UN", "4K", "EX"],
+    ),
+    ("Africa", &["ZS", "5Z", "CN", "EA8", "CT3", "D4", "V5", "9J"]),
+    ("Oceania", &["VK", "ZL", "KH6", "KH2", "KH0", "FK", "P2"]),
+];
+
+/// The association part of a summit code, e.g. `W7A` for `W7A/MN-001`.
+fn summit_association(summit: &str) -> &str {
+    summit.split('/').next().unwrap_or(summit).trim()
+}
+
+/// Continent group for an association code, by longest prefix match.
+fn region_for_association(association: &str) -> Option<&'static str> {
+    let association = association.to_uppercase();
+    REGIONS
+        .iter()
+        .flat_map(|(region, prefixes)| prefixes.iter().map(move |p| (*region, *p)))
+        .filter(|(_, prefix)| association.starts_with(prefix))
+        .max_by_key(|(_, prefix)| prefix.len())
+        .map(|(region, _)| region)
+}
+
+/// The header's display filters, borrowed from component state. Empty
+/// lists let everything through.
+#[derive(Clone, Copy)]
+struct SpotFilters<'a> {
+    band: Option<&'a str>,
+    modes: &'a [String],
+    regions: &'a [String],
+    /// Comma-separated association prefixes, e.g. `W6, W7`.
+    associations: &'a str,
+    /// Text the spot's comments must contain; see `comment_matches`.
+    comments: &'a str,
+}
+
+/// Whether `comments` contain `keyword`, ignoring case. A blank keyword
+/// matches everything.
+fn comment_matches(comments: &str, keyword: &str) -> bool {
+    let keyword = keyword.trim();
+    keyword.is_empty() || comments.to_lowercase().contains(&keyword.to_lowercase())
+}
+
+impl SpotFilters<'_> {
+    fn matches(&self, spot: &Spot) -> bool {
+        let association = summit_association(&spot.summit).to_uppercase();
+        let mut prefixes = self
+            .associations
+            .split(',')
+            .map(|p| p.trim().to_uppercase())
+            .filter(|p| !p.is_empty())
+            .peekable();
+        self.band.is_none_or(|band| in_band(band, spot.frequency_mhz))
+            && (self.modes.is_empty() || self.modes.iter().any(|m| m == mode_group(&spot.mode)))
+            && (self.regions.is_empty()
+                || region_for_association(&association)
+                    .is_some_and(|region| self.regions.iter().any(|r| r == region)))
+            && (prefixes.peek().is_none() || prefixes.any(|p| association.starts_with(&p)))
+            && comment_matches(&spot.comments, self.comments)
+    }
+}
+
+/// Which CAT driver to talk to the radio with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Rig {
+    Kenwood,
+    Yaesu,
+}
+
+impl Rig {
+    const ALL: [Rig; 2] = [Rig::Kenwood, Rig::Yaesu];
+
+    fn key(self) -> &'static str {
+        match self {
+            Rig::Kenwood => "kenwood",
+            Rig::Yaesu => "yaesu",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Rig::Kenwood => "Kenwood",
+            Rig::Yaesu => "Yaesu FT-891/FT-991",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Rig> {
+        Rig::ALL.into_iter().find(|r| r.key() == key)
+    }
+
+    /// The driver for operations every radio supports. Split, read-back
+    /// verification, filter, AGC, RIT and the other Kenwood panel controls
+    /// aren't on `RigDriver` because no other driver implements them yet,
+    /// so those call `KenwoodDriver` directly and are only shown, or run,
+    /// with `Rig::Kenwood` selected.
+    fn driver(self) -> &'static dyn RigDriver {
+        match self {
+            Rig::Kenwood => &KenwoodDriver,
+            Rig::Yaesu => &YaesuDriver,
+        }
+    }
+}
+
+/// Spot table columns that can be sorted by clicking their header.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SortColumn {
+    Time,
+    Callsign,
+    Summit,
+    Frequency,
+    Mode,
+}
+
+impl SortColumn {
+    fn compare(self, a: &Spot, b: &Spot) -> std::cmp::Ordering {
+        match self {
+            SortColumn::Time => a.epoch_ms.unwrap_or(0.0).total_cmp(&b.epoch_ms.unwrap_or(0.0)),
+            SortColumn::Callsign => a.callsign.cmp(&b.callsign),
+            SortColumn::Summit => a.summit.cmp(&b.summit),
+            SortColumn::Frequency => a.frequency_mhz.total_cmp(&b.frequency_mhz),
+            SortColumn::Mode => a.mode.cmp(&b.mode),
+        }
+    }
+
+    /// Whether `spot` has no usable value in this column; such spots sort
+    /// last in either direction.
+    fn is_missing(self, spot: &Spot) -> bool {
+        self == SortColumn::Time && spot.epoch_ms.is_none()
+    }
+}
+
+/// Spot table columns the user can hide. The star and worked buttons
+/// always show since they are controls rather than data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Column {
+    Time,
+    Age,
+    Source,
+    Callsign,
+    Summit,
+    Frequency,
+    Band,
+    Bearing,
+    Mode,
+    Comments,
+}
+
+impl Column {
+    const ALL: [Column; 10] = [
+        Column::Time,
+        Column::Age,
+        Column::Source,
+        Column::Callsign,
+        Column::Summit,
+        Column::Frequency,
+        Column::Band,
+        Column::Bearing,
+        Column::Mode,
+        Column::Comments,
+    ];
+
+    fn key(self) -> &'static str {
+        match self {
+            Column::Time => "time",
+            Column::Age => "age",
+            Column::Source => "source",
+            Column::Callsign => "callsign",
+            Column::Summit => "summit",
+            Column::Frequency => "frequency",
+            Column::Band => "band",
+            Column::Bearing => "bearing",
+            Column::Mode => "mode",
+            Column::Comments => "comments",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Column::Time => "Time",
+            Column::Age => "Age",
+            Column::Source => "Source",
+            Column::Callsign => "Callsign",
+            Column::Summit => "Summit",
+            Column::Frequency => "Frequency",
+            Column::Band => "Band",
+            Column::Bearing => "Bearing",
+            Column::Mode => "Mode",
+            Column::Comments => "Comments",
         }
     }
-    default_value
+
+    fn from_key(key: &str) -> Option<Column> {
+        Column::ALL.into_iter().find(|column| column.key() == key)
+    }
+}
+
+/// `hidden` with `column` shown or hidden in turn, or `None` if that would
+/// hide both Callsign and Frequency and leave rows nobody can act on.
+fn toggle_hidden_column(hidden: &[Column], column: Column) -> Option<Vec<Column>> {
+    let mut next: Vec<Column> = hidden.iter().copied().filter(|c| *c != column).collect();
+    if next.len() == hidden.len() {
+        next.push(column);
+    }
+    let keeps_one = [Column::Callsign, Column::Frequency]
+        .iter()
+        .any(|required| !next.contains(required));
+    keeps_one.then_some(next)
 }
 
-fn save_freq(key: &str, value: f64) {
-    if let Some(storage) = get_storage() {
-        let _ = storage.set_item(key, &value.to_string());
+/// Map a spot's mode onto one of `MODE_GROUPS`.
+fn mode_group(mode: &str) -> &'static str {
+    match mode.trim().to_uppercase().as_str() {
+        "CW" => "CW",
+        "SSB" | "USB" | "LSB" => "SSB",
+        "FT8" => "FT8",
+        "FM" => "FM",
+        _ => "Other",
     }
 }
 
+/// A spot as the SOTA API sends it.
 #[derive(Debug, Clone, Deserialize)]
 struct SpotRaw {
+    id: Option<u64>,
     #[serde(rename = "timeStamp")]
     timestamp: Option<String>,
     #[serde(rename = "activatorCallsign")]
@@ -48,465 +635,4285 @@ struct SpotRaw {
     comments: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct Spot {
+    /// The API's spot id; ids increase as spots are posted. Zero when the
+    /// API didn't send one.
+    #[serde(default)]
+    id: u64,
+    /// Label of the `SpotSource` the spot came from, e.g. `SOTA`.
+    #[serde(default)]
+    source: String,
     timestamp: String,
+    /// `timestamp` parsed to epoch milliseconds, or `None` if it didn't
+    /// parse. Drives sorting and ages; `timestamp` is kept for display.
+    #[serde(default)]
+    epoch_ms: Option<f64>,
     callsign: String,
     summit: String,
     frequency_mhz: f64,
     mode: String,
     comments: String,
+    /// Number of older repeat spots folded into this one by `dedup_spots`.
+    merged: usize,
 }
 
 impl Spot {
     fn from_raw(raw: SpotRaw) -> Option<Self> {
-        let freq = raw.frequency.unwrap_or_default();
-        let freq = freq.trim();
-        let frequency_mhz = freq.parse::<f64>().ok()?;
-        if frequency_mhz <= 0.0 {
+        let frequency_mhz = parse_spot_frequency(&raw.frequency.unwrap_or_default())?;
+        let callsign = raw.callsign.unwrap_or_default().trim().to_uppercase();
+        let summit = raw.summit.unwrap_or_default().trim().to_string();
+        if callsign.is_empty() || summit.is_empty() {
             return None;
         }
+        let timestamp = raw.timestamp.unwrap_or_default();
         Some(Self {
-            timestamp: raw.timestamp.unwrap_or_default(),
-            callsign: raw.callsign.unwrap_or_default(),
-            summit: raw.summit.unwrap_or_default(),
+            id: raw.id.unwrap_or_default(),
+            source: SotaSource::LABEL.to_string(),
+            epoch_ms: timestamp_ms(&timestamp),
+            timestamp,
+            callsign,
+            summit,
             frequency_mhz,
             mode: raw.mode.unwrap_or_default(),
             comments: raw.comments.unwrap_or_default(),
+            merged: 0,
         })
     }
-}
 
-fn format_time(ts: &str) -> String {
-    // Expecting ISO-like timestamp. Extract HH:MM:SS(.sss) if present.
-    if let Some(t_pos) = ts.find('T') {
-        let time_part = &ts[t_pos + 1..];
-        let trimmed = time_part
-            .trim_end_matches('Z')
-            .split(['.', '+', '-'])
-            .next()
-            .unwrap_or(time_part);
-        return trimmed.to_string();
+    /// Whether two spots are the same report: same activator, summit and
+    /// frequency to the nearest 100 Hz.
+    fn same_spot(&self, other: &Spot) -> bool {
+        self.callsign == other.callsign
+            && self.summit == other.summit
+            && (self.frequency_mhz - other.frequency_mhz).abs() < 0.0001
+    }
+
+    /// Identity used by auto-tune so the same spot is never tuned twice.
+    fn tune_key(&self) -> String {
+        format!("{}|{}|{:.4}", self.callsign, self.summit, self.frequency_mhz)
+    }
+
+    /// Key recording a completed QSO: the same activator and summit on
+    /// another band is still worth chasing.
+    fn worked_key(&self) -> String {
+        format!(
+            "{}|{}|{}",
+            self.callsign,
+            self.summit,
+            band_for_freq(self.frequency_mhz).unwrap_or("?")
+        )
+    }
+
+    fn same_band(&self, other: &Spot) -> bool {
+        match (band_for_freq(self.frequency_mhz), band_for_freq(other.frequency_mhz)) {
+            (Some(a), Some(b)) => a == b,
+            _ => (self.frequency_mhz - other.frequency_mhz).abs() < 0.05,
+        }
     }
-    ts.to_string()
 }
 
-#[function_component(App)]
-pub fn app() -> Html {
-    let spots = use_state(Vec::<Spot>::new);
-    let selected_row = use_state(|| None::<usize>);
-    let status = use_state(|| "".to_string());
-    let connected = use_state(|| false);
-    let serial = use_state(SerialManager::new);
-    let min_freq = use_state(|| 7.0_f64);
-    let max_freq = use_state(|| 29.7_f64);
-    let show_settings = use_state(|| false);
-    let raw_cmd = use_state(|| "".to_string());
-    let response_log = use_state(Vec::<String>::new);
-    let last_rx = use_state(|| "".to_string());
+/// Somewhere spots come from. Each source knows its endpoints and how to
+/// map its wire format onto `Spot`; `fetch_spots` merges every enabled
+/// source into one table.
+trait SpotSource {
+    /// Short name shown in the table's Source column.
+    fn label(&self) -> &'static str;
+    fn spots_url(&self) -> String;
+    /// Change-marker endpoint, if the source has one. Sources without it
+    /// are always fetched in full.
+    fn epoch_url(&self) -> Option<String>;
+    fn parse(&self, body: &str) -> Result<Vec<Spot>, String>;
+}
 
-    {
-        let min_freq = min_freq.clone();
-        let max_freq = max_freq.clone();
-        use_effect_with((), move |_| {
-            min_freq.set(load_freq(STORAGE_MIN_FREQ, 7.0));
-            max_freq.set(load_freq(STORAGE_MAX_FREQ, 29.7));
-            || ()
-        });
+/// The SOTAwatch spot API.
+struct SotaSource {
+    api_base: String,
+    count: u32,
+}
+
+impl SotaSource {
+    const LABEL: &'static str = "SOTA";
+}
+
+impl SpotSource for SotaSource {
+    fn label(&self) -> &'static str {
+        Self::LABEL
     }
 
-    {
-        let spots = spots.clone();
-        let status = status.clone();
-        use_effect_with((), move |_| {
-            let fetch = move || {
-                let spots = spots.clone();
-                let status = status.clone();
-                spawn_local(async move {
-                    status.set("Refreshing spots...".to_string());
-                    let response = Request::get(SPOTS_URL).send().await;
-                    match response {
-                        Ok(res) => match res.json::<Vec<SpotRaw>>().await {
-                            Ok(raw) => {
-                                let parsed = raw
-                                    .into_iter()
-                                    .filter_map(Spot::from_raw)
-                                    .collect::<Vec<_>>();
-                                spots.set(parsed);
-                                status.set("".to_string());
-                            }
-                            Err(err) => {
-                                status.set(format!("Failed to parse spots: {}", err));
-                            }
-                        },
-                        Err(err) => {
-                            status.set(format!("Failed to fetch spots: {}", err));
-                        }
-                    }
-                });
-            };
+    fn spots_url(&self) -> String {
+        spots_url(&self.api_base, self.count)
+    }
 
-            fetch();
-            let interval = Interval::new(REFRESH_MS, fetch);
-            move || drop(interval)
-        });
+    fn epoch_url(&self) -> Option<String> {
+        Some(epoch_url(&self.api_base))
     }
 
-    let on_connect = {
-        let serial = serial.clone();
-        let connected = connected.clone();
-        let status = status.clone();
-        Callback::from(move |_| {
-            let serial = serial.clone();
-            let connected = connected.clone();
-            let status = status.clone();
-            spawn_local(async move {
-                status.set("Requesting serial port...".to_string());
-                match serial.connect(9600).await {
-                    Ok(()) => {
-                        connected.set(true);
-                        status.set("Serial connected".to_string());
-                    }
-                    Err(err) => {
-                        status.set(format!("Serial connect failed: {:?}", err));
-                    }
-                }
-            });
-        })
-    };
+    fn parse(&self, body: &str) -> Result<Vec<Spot>, String> {
+        let raw = serde_json::from_str::<Vec<SpotRaw>>(body).map_err(|e| e.to_string())?;
+        Ok(raw.into_iter().filter_map(Spot::from_raw).collect())
+    }
+}
 
-    let on_refresh = {
-        let spots = spots.clone();
-        let status = status.clone();
-        Callback::from(move |_| {
-            let spots = spots.clone();
-            let status = status.clone();
-            spawn_local(async move {
-                status.set("Refreshing spots...".to_string());
-                let response = Request::get(SPOTS_URL).send().await;
-                match response {
-                    Ok(res) => match res.json::<Vec<SpotRaw>>().await {
-                        Ok(raw) => {
-                            let parsed = raw
-                                .into_iter()
-                                .filter_map(Spot::from_raw)
-                                .collect::<Vec<_>>();
-                            spots.set(parsed);
-                            status.set("".to_string());
-                        }
-                        Err(err) => {
-                            status.set(format!("Failed to parse spots: {}", err));
-                        }
-                    },
-                    Err(err) => {
-                        status.set(format!("Failed to fetch spots: {}", err));
-                    }
-                }
-            });
-        })
+/// The sources currently enabled. Only SOTA exists so far.
+fn build_sources(api_base: &str, count: u32) -> Vec<Rc<dyn SpotSource>> {
+    vec![Rc::new(SotaSource { api_base: api_base.to_string(), count })]
+}
+
+/// Parse a SOTA timestamp into milliseconds since the epoch. The API sends
+/// UTC without an offset, which `Date.parse` would otherwise read as local.
+fn timestamp_ms(ts: &str) -> Option<f64> {
+    let ts = ts.trim();
+    if ts.is_empty() {
+        return None;
+    }
+    let has_offset = ts.ends_with('Z')
+        || ts
+            .rfind('T')
+            .is_some_and(|t| ts[t..].contains(['+', '-']));
+    let ms = if has_offset {
+        js_sys::Date::parse(ts)
+    } else {
+        js_sys::Date::parse(&format!("{}Z", ts))
     };
+    (!ms.is_nan()).then_some(ms)
+}
 
-    let on_tune = {
-        let serial = serial.clone();
-        let selected_row = selected_row.clone();
-        let status = status.clone();
-        let connected = connected.clone();
-        let spots = spots.clone();
-        let min_freq = min_freq.clone();
-        let max_freq = max_freq.clone();
-        Callback::from(move |row: usize| {
-            if !*connected {
-                status.set("Connect serial first".to_string());
-                return;
+/// Collapse runs of consecutive spots of the same activator on the same
+/// summit and band, each within `window_minutes` of the one before,
+/// keeping the most recent and counting the rest in `merged`. A repeat
+/// separated by some other spot is left alone.
+fn dedup_spots(spots: Vec<Spot>, window_minutes: f64) -> Vec<Spot> {
+    let window_ms = window_minutes * 60_000.0;
+    let mut kept: Vec<Spot> = Vec::with_capacity(spots.len());
+    let mut previous_ts: Option<f64> = None;
+    for spot in spots {
+        let ts = spot.epoch_ms;
+        let repeat = kept.last().is_some_and(|k| {
+            k.callsign.eq_ignore_ascii_case(&spot.callsign)
+                && k.summit.eq_ignore_ascii_case(&spot.summit)
+                && k.same_band(&spot)
+        }) && matches!((ts, previous_ts), (Some(a), Some(b)) if (a - b).abs() <= window_ms);
+        previous_ts = ts;
+        match kept.last_mut() {
+            Some(k) if repeat => {
+                let merged = k.merged + spot.merged + 1;
+                if ts > k.epoch_ms {
+                    *k = spot;
+                }
+                k.merged = merged;
             }
+            _ => kept.push(spot),
+        }
+    }
+    kept
+}
 
-            let serial = serial.clone();
-            let selected_row = selected_row.clone();
-            let status = status.clone();
-            let spots = spots.clone();
-            let min_freq = *min_freq;
-            let max_freq = *max_freq;
-            spawn_local(async move {
-                if let Some(spot) = spots.get(row) {
-                    if spot.frequency_mhz < min_freq || spot.frequency_mhz > max_freq {
-                        status.set(format!(
-                            "Blocked: {:.3} MHz outside {:.3}–{:.3} MHz",
-                            spot.frequency_mhz, min_freq, max_freq
-                        ));
-                        return;
-                    }
-                    let freq_hz = (spot.frequency_mhz * 1_000_000.0).round() as u64;
-                    status.set(format!("Tuning {} MHz {}", spot.frequency_mhz, spot.mode));
-                    match serial.tune_kenwood_ts570(freq_hz, &spot.mode).await {
-                        Ok(()) => {
-                            selected_row.set(Some(row));
-                            status.set("Tuned".to_string());
-                        }
-                        Err(err) => {
-                            status.set(format!("Tune failed: {:?}", err));
-                        }
-                    }
-                }
-            });
-        })
-    };
+/// Summit details from the SOTA summit database.
+#[derive(Debug, Clone, Deserialize)]
+struct SummitInfo {
+    name: Option<String>,
+    points: Option<u32>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+}
 
-    use gloo_timers::future::TimeoutFuture;
+impl SummitInfo {
+    fn latlon(&self) -> Option<(f64, f64)> {
+        Some((self.latitude?, self.longitude?))
+    }
+}
 
-    let on_toggle_settings = {
-        let show_settings = show_settings.clone();
-        let serial = serial.clone();
-        let response_log = response_log.clone();
-        let last_rx_handle = last_rx.clone();
-        Callback::from(move |_| {
-            let currently = *show_settings;
-            // open settings
-            if !currently {
-                show_settings.set(true);
-                // start buffer drain while settings are open
-                serial.spawn_buffer_drain();
-
-                // start background read loop using the persistent reader
-                let show_settings_clone = show_settings.clone();
-                let serial_clone = serial.clone();
-                let response_log_clone = response_log.clone();
-                let last_rx_clone = last_rx_handle.clone();
-                spawn_local(async move {
-                    while *show_settings_clone {
-                        match serial_clone.read_from_persistent_reader().await {
-                            Ok(resp) if !resp.is_empty() => {
-                                // push to response log and update visible last_rx
-                                let mut v = (*response_log_clone).clone();
-                                let entry = format!("RX: {}", resp);
-                                web_sys::console::log_1(&JsValue::from_str(&format!("app: pushing {}", entry)));
-                                v.push(entry.clone());
-                                response_log_clone.set(v);
-                                last_rx_clone.set(entry.clone());
-                                // Log state sizes to help diagnose why DOM isn't updating
-                                web_sys::console::log_1(&JsValue::from_str(&format!(
-                                    "app: response_log length = {} last_rx = {}",
-                                    (*response_log_clone).len(), (*last_rx_clone).clone()
-                                )));
-                            }
-                            _ => {
-                                // no data this iteration
-                            }
-                        }
-                        TimeoutFuture::new(200).await;
-                    }
-                });
+/// Mean Earth radius, in km.
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Centre of a 4- or 6-character Maidenhead locator such as `CN87` or
+/// `CN87ts`, as (latitude, longitude) in degrees.
+fn grid_to_latlon(grid: &str) -> Option<(f64, f64)> {
+    let g = grid.trim().as_bytes();
+    if g.len() != 4 && g.len() != 6 {
+        return None;
+    }
+    let field = |b: u8| (b'A'..=b'R').contains(&b.to_ascii_uppercase()).then(|| (b.to_ascii_uppercase() - b'A') as f64);
+    let square = |b: u8| b.is_ascii_digit().then(|| (b - b'0') as f64);
+    let subsquare = |b: u8| (b'A'..=b'X').contains(&b.to_ascii_uppercase()).then(|| (b.to_ascii_uppercase() - b'A') as f64);
+
+    let mut lon = -180.0 + field(g[0])? * 20.0 + square(g[2])? * 2.0;
+    let mut lat = -90.0 + field(g[1])? * 10.0 + square(g[3])?;
+    if g.len() == 6 {
+        lon += subsquare(g[4])? * (2.0 / 24.0) + 1.0 / 24.0;
+        lat += subsquare(g[5])? * (1.0 / 24.0) + 0.5 / 24.0;
+    } else {
+        lon += 1.0;
+        lat += 0.5;
+    }
+    Some((lat, lon))
+}
+
+/// Great-circle distance between two (latitude, longitude) points, in km.
+fn distance_km(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lon1) = (from.0.to_radians(), from.1.to_radians());
+    let (lat2, lon2) = (to.0.to_radians(), to.1.to_radians());
+    let a = ((lat2 - lat1) / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+/// Initial great-circle bearing from `from` to `to`, in degrees 0–360.
+fn bearing_deg(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lon1) = (from.0.to_radians(), from.1.to_radians());
+    let (lat2, lon2) = (to.0.to_radians(), to.1.to_radians());
+    let dlon = lon2 - lon1;
+    let y = dlon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
+/// Summit lookups keyed by summit code. `None` marks a lookup that is in
+/// flight or failed, so the code isn't requested again.
+type SummitCache = Rc<RefCell<HashMap<String, Option<SummitInfo>>>>;
+
+/// The last successful spot list, kept so the table has something to show
+/// when the app opens offline.
+#[derive(Serialize, Deserialize)]
+struct CachedSpots {
+    /// When the list was fetched, in epoch milliseconds.
+    saved_ms: f64,
+    spots: Vec<Spot>,
+}
+
+fn load_spots_cache() -> Option<CachedSpots> {
+    let mut cache: CachedSpots = serde_json::from_str(&settings::get::<String>(STORAGE_SPOTS_CACHE)?).ok()?;
+    // Caches saved before `epoch_ms` existed still carry the raw timestamp.
+    for spot in cache.spots.iter_mut().filter(|spot| spot.epoch_ms.is_none()) {
+        spot.epoch_ms = timestamp_ms(&spot.timestamp);
+    }
+    Some(cache)
+}
+
+fn save_spots_cache(spots: &[Spot]) {
+    let cache = CachedSpots {
+        saved_ms: js_sys::Date::now(),
+        spots: spots.to_vec(),
+    };
+    if let Ok(json) = serde_json::to_string(&cache) {
+        settings::set(STORAGE_SPOTS_CACHE, &json);
+    }
+}
+
+/// Everything a spot refresh needs. Cloned into the refresh interval, so
+/// settings it reads live behind shared cells rather than state snapshots.
+#[derive(Clone)]
+struct FetchContext {
+    spots: UseStateHandle<Vec<Spot>>,
+    status: StatusHandle,
+    sources: Rc<RefCell<Vec<Rc<dyn SpotSource>>>>,
+    dedup_minutes: Rc<RefCell<f64>>,
+    summits: SummitCache,
+    redraw: UseForceUpdateHandle,
+    /// When the next automatic refresh is due, in epoch milliseconds.
+    next_refresh_ms: Rc<RefCell<f64>>,
+    /// Automatic refresh interval, in seconds.
+    refresh_secs: Rc<RefCell<u32>>,
+    /// Epochs seen at the last successful fetch, joined across sources;
+    /// `None` forces a full fetch.
+    epoch: Rc<RefCell<Option<String>>>,
+    /// Consecutive failed fetches, used to pick the next retry delay.
+    retry_attempt: Rc<RefCell<usize>>,
+    /// Set while the table shows the localStorage cache instead of a fresh
+    /// fetch; holds the cache's timestamp.
+    cached_at: UseStateHandle<Option<f64>>,
+    /// Whether `spots` holds a fetched list (or the cache of one), so an
+    /// empty table can say "nothing yet" rather than "nothing matches".
+    loaded: UseStateHandle<bool>,
+    /// Bodies from the last fetch, pretty-printed, for the debug panel.
+    /// `None` while the panel is off so large responses aren't kept around.
+    raw_response: Rc<RefCell<Option<String>>>,
+}
+
+/// Pretty-print a JSON body for display; anything else is shown as is.
+fn pretty_json(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|value| serde_json::to_string_pretty(&value).ok())
+        .unwrap_or_else(|| body.to_string())
+}
+
+/// Post `spot` to the spots API, authorised by the user's `token`. Kept
+/// apart from the read-only fetch path: it is the only request that
+/// carries credentials, and the token is never logged. Returns the API's
+/// reply, or the status and body of a refusal.
+async fn post_self_spot(api_base: &str, token: &str, spot: &SelfSpot) -> Result<String, String> {
+    let url = api_base.trim().trim_end_matches('/');
+    let request = Request::post(url)
+        .header("Authorization", &format!("Bearer {}", token.trim()))
+        .json(spot)
+        .map_err(|e| format!("Could not build the spot: {}", e))?;
+    let res = request.send().await.map_err(|e| format!("Network error: {}", e))?;
+    let body = res.text().await.unwrap_or_default();
+    if res.ok() {
+        Ok(pretty_json(&body))
+    } else {
+        Err(format!("{} {}: {}", res.status(), res.status_text(), body.chars().take(200).collect::<String>()))
+    }
+}
+
+/// Fetch the latest spots into `spots`, reporting progress and failures
+/// through `status`.
+fn fetch_spots(ctx: FetchContext) {
+    *ctx.next_refresh_ms.borrow_mut() = js_sys::Date::now() + *ctx.refresh_secs.borrow() as f64 * 1000.0;
+    spawn_local(async move {
+        let status = ctx.status.clone();
+        status.push_status(StatusLevel::Info, "Refreshing spots...");
+
+        // Ask for the epochs first: if none has moved there's nothing new.
+        // When any can't be read, fetch anyway.
+        let sources = ctx.sources.borrow().clone();
+        let previous_epoch = ctx.epoch.borrow().clone();
+        let mut epochs = Vec::new();
+        for source in &sources {
+            let epoch = match source.epoch_url() {
+                Some(url) => match Request::get(&url).send().await {
+                    Ok(res) if res.ok() => res.text().await.ok().map(|e| e.trim().to_string()),
+                    _ => None,
+                },
+                None => None,
+            };
+            epochs.push(epoch);
+        }
+        let epoch = epochs.into_iter().collect::<Option<Vec<_>>>().map(|e| e.join("|"));
+        if epoch.is_some() && epoch == previous_epoch {
+            *ctx.retry_attempt.borrow_mut() = 0;
+            status.push_status(StatusLevel::Info, "No new spots");
+            return;
+        }
+
+        let mut fetched = Vec::new();
+        let mut parse_failed = false;
+        if let Some(raw) = ctx.raw_response.borrow_mut().as_mut() {
+            raw.clear();
+        }
+        for source in &sources {
+            let body = match Request::get(&source.spots_url()).send().await {
+                Ok(res) => res.text().await,
+                Err(err) => Err(err),
+            };
+            let body = match body {
+                Ok(body) => body,
+                Err(err) => {
+                    web_sys::console::log_1(
+                        &format!("Network error fetching {} spots: {}", source.label(), err).into(),
+                    );
+                    schedule_retry(&ctx);
+                    return;
+                }
+            };
+            if let Some(raw) = ctx.raw_response.borrow_mut().as_mut() {
+                raw.push_str(&format!("// {} {}\n{}\n", source.label(), source.spots_url(), pretty_json(&body)));
+                ctx.redraw.force_update();
+            }
+            match source.parse(&body) {
+                Ok(spots) => fetched.extend(spots),
+                Err(err) => {
+                    // The API sometimes answers with an HTML error page; show
+                    // enough of it to tell that apart from a schema change.
+                    let preview = body.chars().take(200).collect::<String>();
+                    web_sys::console::log_1(
+                        &format!("{} API returned unparseable body: {:?}", source.label(), preview).into(),
+                    );
+                    status.push_status(
+                        StatusLevel::Error,
+                        format!("{} API format error: {} (response logged to console)", source.label(), err),
+                    );
+                    parse_failed = true;
+                }
+            }
+        }
+        if parse_failed && fetched.is_empty() {
+            return;
+        }
+        let parsed = dedup_spots(fetched, *ctx.dedup_minutes.borrow());
+        lookup_summits(&parsed, &ctx.summits, &ctx.redraw);
+        *ctx.retry_attempt.borrow_mut() = 0;
+        // A source that failed to parse is retried in full next time.
+        *ctx.epoch.borrow_mut() = if parse_failed { None } else { epoch };
+        save_spots_cache(&parsed);
+        ctx.cached_at.set(None);
+        ctx.loaded.set(true);
+        ctx.spots.set(parsed);
+    });
+}
+
+/// Where the spot at `row` in `old` sits in `new`, matching on callsign,
+/// summit and frequency since a refresh shifts indices.
+fn remap_row(old: &[Spot], new: &[Spot], row: Option<usize>) -> Option<usize> {
+    let spot = old.get(row?)?;
+    new.iter().position(|s| s.same_spot(spot))
+}
+
+/// Pull the next refresh forward after a failed fetch, backing off through
+/// `RETRY_DELAYS_MS` before giving up until the normal interval.
+fn schedule_retry(ctx: &FetchContext) {
+    let attempt = {
+        let mut attempt = ctx.retry_attempt.borrow_mut();
+        *attempt += 1;
+        *attempt
+    };
+    match RETRY_DELAYS_MS.get(attempt - 1) {
+        Some(&delay) => {
+            *ctx.next_refresh_ms.borrow_mut() = js_sys::Date::now() + delay as f64;
+            ctx.status.push_status(
+                StatusLevel::Warning,
+                format!(
+                    "Fetch failed, retrying in {}s (attempt {})",
+                    delay / 1000,
+                    attempt
+                ),
+            );
+        }
+        None => {
+            *ctx.retry_attempt.borrow_mut() = 0;
+            ctx.status.push_status(
+                StatusLevel::Warning,
+                format!(
+                    "Fetch failed after {} retries; next try in {}s",
+                    RETRY_DELAYS_MS.len(),
+                    *ctx.refresh_secs.borrow()
+                ),
+            );
+        }
+    }
+}
+
+/// Start background lookups for summit codes not yet in `cache`. Each
+/// result lands in the cache and triggers a redraw; failures are left as
+/// `None` so the table keeps showing the bare code.
+fn lookup_summits(spots: &[Spot], cache: &SummitCache, redraw: &UseForceUpdateHandle) {
+    for spot in spots {
+        let code = spot.summit.clone();
+        if code.is_empty() || cache.borrow().contains_key(&code) {
+            continue;
+        }
+        cache.borrow_mut().insert(code.clone(), None);
+        let cache = cache.clone();
+        let redraw = redraw.clone();
+        spawn_local(async move {
+            let url = format!("{}{}", SUMMIT_URL, code);
+            let info = match Request::get(&url).send().await {
+                Ok(res) if res.ok() => res.json::<SummitInfo>().await.ok(),
+                _ => None,
+            };
+            if info.is_some() {
+                cache.borrow_mut().insert(code, info);
+                redraw.force_update();
+            }
+        });
+    }
+}
+
+/// Parse the API's frequency field leniently: take the first numeric token
+/// ("14.062 CW", "7.032 (QSX 7.035)") and accept a comma as the decimal
+/// separator. Empty and zero frequencies are rejected.
+fn parse_spot_frequency(field: &str) -> Option<f64> {
+    let start = field.find(|c: char| c.is_ascii_digit())?;
+    let token = field[start..]
+        .split(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .next()?
+        .trim_end_matches(['.', ',']);
+    token
+        .replace(',', ".")
+        .parse::<f64>()
+        .ok()
+        .filter(|mhz| mhz.is_finite() && *mhz > 0.0)
+}
+
+/// Parse a typed frequency, accepting either "14.062" or "14,062".
+fn parse_decimal(input: &str) -> Option<f64> {
+    input.trim().replace(',', ".").parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/// Parse a number from a comment token, tolerating a trailing `k`/`khz`.
+fn parse_comment_number(token: &str) -> Option<f64> {
+    let token = token.trim_end_matches("khz").trim_end_matches('k');
+    token.parse::<f64>().ok().filter(|v| v.is_finite() && *v > 0.0)
+}
+
+/// Modes offered for manual tuning.
+const MANUAL_MODES: &[&str] = &["CW", "USB", "LSB", "FM", "AM", "FT8"];
+/// Manual mode used when the band has no remembered mode.
+const DEFAULT_MANUAL_MODE: &str = "CW";
+
+/// Lowest and highest frequencies the radio will accept, in MHz.
+const RADIO_MIN_MHZ: f64 = 0.5;
+const RADIO_MAX_MHZ: f64 = 60.0;
+
+const RANGE_INVERTED: &str = "Min must be below max";
+const TUNE_BUSY: &str = "Tuning in progress...";
+
+/// Conventional USB dial frequencies for the weak-signal digital modes, in MHz.
+const FT8_DIALS: &[f64] = &[1.840, 3.573, 7.074, 10.136, 14.074, 18.100, 21.074, 24.915, 28.074, 50.313];
+const FT4_DIALS: &[f64] = &[3.575, 7.0475, 10.140, 14.080, 18.104, 21.140, 24.919, 28.180, 50.318];
+/// Width of the audio passband above the dial, in MHz. Spots inside it are
+/// an audio offset from the dial rather than a real RF frequency.
+const DIGITAL_PASSBAND_MHZ: f64 = 0.0035;
+
+/// For an FT8/FT4 spot sitting in the passband above its band's dial, the
+/// dial frequency to tune instead. Spots already on the dial, or outside any
+/// passband, are left alone.
+fn digital_dial(mode: &str, mhz: f64) -> Option<f64> {
+    let dials = match mode.trim().to_uppercase().as_str() {
+        "FT8" => FT8_DIALS,
+        "FT4" => FT4_DIALS,
+        _ => return None,
+    };
+    dials.iter().copied().find(|&dial| {
+        let offset = mhz - dial;
+        offset > 1e-6 && offset <= DIGITAL_PASSBAND_MHZ
+    })
+}
+
+/// How often and how many times to poll the tuner after starting it.
+const ATU_POLL_MS: u32 = 500;
+const ATU_POLLS: usize = 20;
+
+/// Receive bandwidth presets, in Hz, offered per mode family.
+const CW_WIDTH_PRESETS: &[u32] = &[250, 500, 1000];
+const SSB_WIDTH_PRESETS: &[u32] = &[1800, 2400];
+
+/// Receive widths to set after tuning, per mode family.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TuneFilter {
+    cw_hz: u32,
+    ssb_hz: u32,
+}
+
+/// After a Kenwood tune, send the preferred width for `mode`. Returns the
+/// width applied, or `None` for modes without an adjustable width.
+async fn apply_tune_filter(serial: &SerialManager, mode: &str, filter: TuneFilter) -> Result<Option<u32>, JsValue> {
+    let Some(code) = KenwoodDriver::expected_mode_code(mode) else {
+        return Ok(None);
+    };
+    let width = match code {
+        3 | 6 | 7 | 9 => filter.cw_hz,
+        1 | 2 => filter.ssb_hz,
+        _ => return Ok(None),
+    };
+    let Some((commands, applied)) = filter_width_commands(code, width) else {
+        return Ok(None);
+    };
+    TimeoutFuture::new(serial.command_delay()).await;
+    serial.send_sequence(&commands).await?;
+    Ok(Some(applied))
+}
+
+/// How far each RIT +/- button moves the clarifier, in Hz.
+const RIT_BUTTON_HZ: i32 = 50;
+
+/// Check a frequency against the user's tuning window, returning the
+/// status message to show when it's blocked.
+fn check_tune_guard(mhz: f64, min_freq: f64, max_freq: f64) -> Result<(), String> {
+    if min_freq >= max_freq {
+        return Err(RANGE_INVERTED.to_string());
+    }
+    if mhz < min_freq || mhz > max_freq {
+        return Err(format!(
+            "Blocked: {:.3} MHz outside {:.3}–{:.3} MHz",
+            mhz, min_freq, max_freq
+        ));
+    }
+    Ok(())
+}
+
+/// A band-plan segment and the modes allowed to tune inside it. Modes are
+/// matched exactly ("USB") or by group ("SSB").
+#[derive(Debug, Clone, PartialEq)]
+struct SegmentRule {
+    lo_mhz: f64,
+    hi_mhz: f64,
+    modes: Vec<String>,
+}
+
+/// Parse segment rules, one per line, e.g. `14.000-14.070 CW FT8`. Blank
+/// lines and `#` comments are skipped.
+fn parse_segment_rules(text: &str) -> Result<Vec<SegmentRule>, String> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.split('#').next().unwrap_or("").trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(number, line)| {
+            let error = |msg: &str| format!("Segment rule {}: {}", number, msg);
+            let mut parts = line.split_whitespace();
+            let range = parts.next().unwrap_or_default();
+            let (lo, hi) = range
+                .split_once('-')
+                .ok_or_else(|| error("expected LOW-HIGH in MHz"))?;
+            let (Some(lo_mhz), Some(hi_mhz)) = (parse_decimal(lo), parse_decimal(hi)) else {
+                return Err(error("invalid frequency"));
+            };
+            if lo_mhz >= hi_mhz {
+                return Err(error("low edge must be below high edge"));
+            }
+            let modes: Vec<String> = parts.map(|mode| mode.to_uppercase()).collect();
+            if modes.is_empty() {
+                return Err(error("list at least one allowed mode"));
+            }
+            Ok(SegmentRule { lo_mhz, hi_mhz, modes })
+        })
+        .collect()
+}
+
+/// Check `mode` against every segment rule containing `mhz`, returning the
+/// status message to show when it's blocked.
+fn check_segment_rules(rules: &[SegmentRule], mhz: f64, mode: &str) -> Result<(), String> {
+    let mode = mode.trim().to_uppercase();
+    let group = mode_group(&mode);
+    let blocking = rules.iter().find(|rule| {
+        mhz >= rule.lo_mhz && mhz <= rule.hi_mhz && !rule.modes.iter().any(|m| *m == mode || m == group)
+    });
+    match blocking {
+        Some(rule) => Err(format!(
+            "Blocked: {} not allowed in {:.3}–{:.3} MHz ({} only)",
+            mode,
+            rule.lo_mhz,
+            rule.hi_mhz,
+            rule.modes.join("/")
+        )),
+        None => Ok(()),
+    }
+}
+
+/// A listening-frequency phrase found in spot comments: its byte range in
+/// the comment text and the frequency it points at, in MHz.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ListenHint {
+    start: usize,
+    end: usize,
+    mhz: f64,
+}
+
+/// Offsets larger than this are taken as unrelated numbers, not "up N".
+const MAX_SPLIT_OFFSET_KHZ: f64 = 50.0;
+
+/// Find every listening-frequency hint in spot comments: "up 1", "dn 2",
+/// "QSX 14.025" and "listening on 7.035". Other numbers are ignored.
+fn find_listen_hints(comments: &str, spot_mhz: f64) -> Vec<ListenHint> {
+    let mut tokens = Vec::new();
+    let mut token_start = None;
+    for (i, c) in comments.char_indices().chain([(comments.len(), ' ')]) {
+        let separator = c.is_whitespace() || matches!(c, ',' | ';' | '(' | ')');
+        match (separator, token_start) {
+            (true, Some(start)) => {
+                tokens.push((start, i, comments[start..i].to_lowercase()));
+                token_start = None;
+            }
+            (false, None) => token_start = Some(i),
+            _ => {}
+        }
+    }
+
+    // A keyword's value is either glued on ("up1", "qsx14025") or the next
+    // token; returns the value and the index of the last token consumed.
+    let value_after = |i: usize, rest: &str| -> Option<(f64, usize)> {
+        if rest.is_empty() {
+            Some((parse_comment_number(&tokens.get(i + 1)?.2)?, i + 1))
+        } else {
+            Some((parse_comment_number(rest)?, i))
+        }
+    };
+    // Accept both "QSX 14.025" (MHz) and "QSX 14025" (kHz).
+    let absolute_mhz = |value: f64| {
+        let mhz = if value >= 1000.0 { value / 1000.0 } else { value };
+        Some(mhz).filter(|mhz| (RADIO_MIN_MHZ..=RADIO_MAX_MHZ).contains(mhz))
+    };
+
+    let mut hints = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i].2.as_str();
+        let mut found = None;
+        for (keyword, sign) in [("up", 1.0), ("dn", -1.0), ("down", -1.0)] {
+            if let Some(rest) = token.strip_prefix(keyword) {
+                found = value_after(i, rest)
+                    .filter(|(khz, _)| *khz <= MAX_SPLIT_OFFSET_KHZ)
+                    .map(|(khz, last)| (spot_mhz + sign * khz / 1000.0, last));
+                if found.is_some() {
+                    break;
+                }
+            }
+        }
+        if found.is_none() {
+            if let Some(rest) = token.strip_prefix("qsx") {
+                found = value_after(i, rest).and_then(|(v, last)| Some((absolute_mhz(v)?, last)));
+            } else if token == "listening" {
+                let skip_on = tokens.get(i + 1).is_some_and(|t| t.2 == "on");
+                let at = if skip_on { i + 1 } else { i };
+                found = value_after(at, "").and_then(|(v, last)| Some((absolute_mhz(v)?, last)));
+            }
+        }
+        match found {
+            Some((mhz, last)) => {
+                hints.push(ListenHint { start: tokens[i].0, end: tokens[last].1, mhz });
+                i = last + 1;
+            }
+            None => i += 1,
+        }
+    }
+    hints
+}
+
+/// The newest spot not in `seen` that `matches` accepts, as an index into
+/// `spots`, for auto-tune.
+fn pick_auto_tune(spots: &[Spot], seen: &HashSet<String>, matches: impl Fn(&Spot) -> bool) -> Option<usize> {
+    spots
+        .iter()
+        .enumerate()
+        .filter(|(_, spot)| !seen.contains(&spot.tune_key()) && matches(spot))
+        .max_by(|(_, a), (_, b)| {
+            a.epoch_ms.unwrap_or(f64::NEG_INFINITY).total_cmp(&b.epoch_ms.unwrap_or(f64::NEG_INFINITY))
+        })
+        .map(|(idx, _)| idx)
+}
+
+/// Spot map size in SVG units: one unit per degree, equirectangular.
+const MAP_WIDTH: f64 = 360.0;
+const MAP_HEIGHT: f64 = 180.0;
+
+/// Project a latitude/longitude onto the spot map.
+fn map_point((lat, lon): (f64, f64)) -> (f64, f64) {
+    (lon.clamp(-180.0, 180.0) + 180.0, 90.0 - lat.clamp(-90.0, 90.0))
+}
+
+/// A plotted spot: its index into `spots`, position and hover text.
+struct MapMarker {
+    idx: usize,
+    latlon: (f64, f64),
+    band: Option<&'static str>,
+    title: String,
+}
+
+/// Offline world map of spots as dots coloured by band, on a 30° graticule.
+/// Clicking a dot emits the spot's index.
+fn render_spot_map(
+    markers: &[MapMarker],
+    qth: Option<(f64, f64)>,
+    highlighted: Option<usize>,
+    on_select: &Callback<usize>,
+) -> Html {
+    html! {
+        <svg class="spot-map" viewBox={format!("0 0 {} {}", MAP_WIDTH, MAP_HEIGHT)} role="img" aria-label="Map of spotted summits">
+            <rect class="sea" width={MAP_WIDTH.to_string()} height={MAP_HEIGHT.to_string()} />
+            { for (1..12).map(|i| {
+                let x = (i * 30).to_string();
+                html! { <line class="graticule" x1={x.clone()} y1="0" x2={x} y2={MAP_HEIGHT.to_string()} /> }
+            }) }
+            { for (1..6).map(|i| {
+                let y = (i * 30).to_string();
+                html! { <line class="graticule" x1="0" y1={y.clone()} x2={MAP_WIDTH.to_string()} y2={y} /> }
+            }) }
+            { match qth.map(map_point) {
+                Some((x, y)) => html! {
+                    <circle class="qth" cx={x.to_string()} cy={y.to_string()} r="2.5"><title>{"Your grid"}</title></circle>
+                },
+                None => html! {},
+            } }
+            { for markers.iter().map(|marker| {
+                let (x, y) = map_point(marker.latlon);
+                let idx = marker.idx;
+                let on_select = on_select.clone();
+                html! {
+                    <circle
+                        class={classes!(
+                            "spot-marker",
+                            marker.band.map(|band| format!("band-{}", band)),
+                            (highlighted == Some(idx)).then_some("highlighted"),
+                        )}
+                        cx={x.to_string()}
+                        cy={y.to_string()}
+                        r="2"
+                        onclick={Callback::from(move |_| on_select.emit(idx))}
+                    ><title>{ marker.title.clone() }</title></circle>
+                }
+            }) }
+        </svg>
+    }
+}
+
+/// Spot comments with each listening-frequency hint turned into a link that
+/// tunes there in the spot's mode. Clicks don't reach the row, which tunes
+/// the spotted frequency instead.
+fn render_comments(spot: &Spot, tune_to: &Callback<(f64, String)>) -> Html {
+    let text = &spot.comments;
+    let mut pos = 0;
+    let mut parts = Vec::new();
+    for hint in find_listen_hints(text, spot.frequency_mhz) {
+        parts.push(html! { { &text[pos..hint.start] } });
+        let onclick = {
+            let tune_to = tune_to.clone();
+            let mode = spot.mode.clone();
+            Callback::from(move |e: MouseEvent| {
+                e.prevent_default();
+                e.stop_propagation();
+                tune_to.emit((hint.mhz, mode.clone()));
+            })
+        };
+        parts.push(html! {
+            <a href="#" class="qsx" title={format!("Tune {:.4} MHz", hint.mhz)} {onclick}>
+                { &text[hint.start..hint.end] }
+            </a>
+        });
+        pos = hint.end;
+    }
+    parts.push(html! { { &text[pos..] } });
+    parts.into_iter().collect::<Html>()
+}
+
+/// The activator's listening frequency in MHz, from the first split hint
+/// in the comments.
+fn parse_split_hint(comments: &str, spot_mhz: f64) -> Option<f64> {
+    find_listen_hints(comments, spot_mhz).first().map(|hint| hint.mhz)
+}
+
+/// Callsigns of other displayed spots within `threshold_khz` of `spot`,
+/// i.e. activators likely to QRM each other.
+fn clashing_calls<'a>(spot: &Spot, others: &[(usize, &'a Spot)], threshold_khz: f64) -> Vec<&'a str> {
+    others
+        .iter()
+        .map(|&(_, other)| other)
+        .filter(|other| other.callsign != spot.callsign)
+        .filter(|other| (other.frequency_mhz - spot.frequency_mhz).abs() * 1000.0 < threshold_khz)
+        .map(|other| other.callsign.as_str())
+        .collect()
+}
+
+/// Whether `callsign` is on the comma-separated `watchlist`. Matching is
+/// case-insensitive and ignores portable prefixes/suffixes, so `K6EL`
+/// matches `K6EL/P`.
+fn is_watched(watchlist: &str, callsign: &str) -> bool {
+    let callsign = callsign.to_uppercase();
+    watchlist
+        .split(',')
+        .map(|w| w.trim().to_uppercase())
+        .filter(|w| !w.is_empty())
+        .any(|w| callsign == w || callsign.split('/').any(|part| part == w))
+}
+
+/// Whether spot comments mention S2S as a word, in any case.
+fn mentions_s2s(comments: &str) -> bool {
+    comments
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|word| word.eq_ignore_ascii_case("s2s"))
+}
+
+/// Whether a spot looks like a summit-to-summit chance while the user is
+/// activating `my_summit`: the comments mention S2S or name that summit.
+/// Nothing matches until a summit is entered.
+fn is_s2s_candidate(spot: &Spot, my_summit: &str) -> bool {
+    let my_summit = my_summit.trim();
+    if my_summit.is_empty() {
+        return false;
+    }
+    mentions_s2s(&spot.comments) || spot.comments.to_uppercase().contains(&my_summit.to_uppercase())
+}
+
+/// Play a short alert tone through the Web Audio API. Failures are ignored;
+/// the visual highlight is the primary signal.
+fn play_beep() {
+    let Ok(ctx) = web_sys::AudioContext::new() else {
+        return;
+    };
+    let (Ok(osc), Ok(gain)) = (ctx.create_oscillator(), ctx.create_gain()) else {
+        return;
+    };
+    osc.frequency().set_value(880.0);
+    gain.gain().set_value(0.1);
+    let _ = osc.connect_with_audio_node(&gain);
+    let _ = gain.connect_with_audio_node(&ctx.destination());
+    let _ = osc.start();
+    let _ = osc.stop_with_when(ctx.current_time() + 0.2);
+    Timeout::new(500, move || {
+        let _ = ctx.close();
+    })
+    .forget();
+}
+
+/// Rows rendered per page. Lists at or below this size render whole, with
+/// no pager.
+const PAGE_SIZE: usize = 50;
+
+/// The `start..end` slice of a `len`-row list shown on `page`, with the page
+/// clamped to the last one.
+fn page_bounds(len: usize, page: usize, page_size: usize) -> (usize, usize) {
+    let last_page = len.saturating_sub(1) / page_size;
+    let start = page.min(last_page) * page_size;
+    (start, (start + page_size).min(len))
+}
+
+/// Most entries kept in the recently-tuned list.
+const MAX_TUNE_HISTORY: usize = 10;
+
+/// Put a tuned frequency and mode at the front of `history`, skipping an
+/// immediate repeat of the newest entry.
+fn push_tune_history(history: &mut Vec<(f64, String)>, mhz: f64, mode: &str) {
+    if let Some((last_mhz, last_mode)) = history.first() {
+        if (last_mhz - mhz).abs() < 1e-7 && last_mode.eq_ignore_ascii_case(mode) {
+            return;
+        }
+    }
+    history.insert(0, (mhz, mode.to_string()));
+    history.truncate(MAX_TUNE_HISTORY);
+}
+
+/// A spot the user tuned, kept for ADIF export.
+#[derive(Debug, Clone)]
+struct TunedContact {
+    callsign: String,
+    summit: String,
+    frequency_mhz: f64,
+    mode: String,
+    tuned_at_ms: f64,
+}
+
+/// One ADIF field with its length prefix, e.g. `<CALL:4>K6EL`.
+fn adif_field(name: &str, value: &str) -> String {
+    format!("<{}:{}>{}", name, value.len(), value)
+}
+
+/// Build an ADIF document with one record per tuned contact. Times are
+/// written in UTC from when the spot was tuned.
+fn build_adif(contacts: &[TunedContact]) -> String {
+    let mut out = String::from("SOTA Chaser export\n");
+    out.push_str(&adif_field("ADIF_VER", "3.1.4"));
+    out.push_str(&adif_field("PROGRAMID", "SOTAChaserWeb"));
+    out.push_str("<EOH>\n");
+    for contact in contacts {
+        let when = js_sys::Date::new(&JsValue::from_f64(contact.tuned_at_ms));
+        let date = format!(
+            "{:04}{:02}{:02}",
+            when.get_utc_full_year(),
+            when.get_utc_month() + 1,
+            when.get_utc_date()
+        );
+        let time = format!(
+            "{:02}{:02}{:02}",
+            when.get_utc_hours(),
+            when.get_utc_minutes(),
+            when.get_utc_seconds()
+        );
+        let mode = contact.mode.trim().to_uppercase();
+        out.push_str(&adif_field("CALL", &contact.callsign));
+        out.push_str(&adif_field("QSO_DATE", &date));
+        out.push_str(&adif_field("TIME_ON", &time));
+        out.push_str(&adif_field("FREQ", &format!("{:.4}", contact.frequency_mhz)));
+        match mode.as_str() {
+            // ADIF files sidebands as submodes of SSB.
+            "USB" | "LSB" => {
+                out.push_str(&adif_field("MODE", "SSB"));
+                out.push_str(&adif_field("SUBMODE", &mode));
+            }
+            "FT4" => {
+                out.push_str(&adif_field("MODE", "MFSK"));
+                out.push_str(&adif_field("SUBMODE", "FT4"));
+            }
+            "" => {}
+            _ => out.push_str(&adif_field("MODE", &mode)),
+        }
+        out.push_str(&adif_field("SOTA_REF", &contact.summit));
+        out.push_str("<EOR>\n");
+    }
+    out
+}
+
+/// Offer `contents` to the user as a file download.
+fn download_text(filename: &str, mime: &str, contents: &str) -> Result<(), JsValue> {
+    let parts = js_sys::Array::of1(&JsValue::from_str(contents));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or_else(|| JsValue::from_str("no document"))?;
+    let anchor: web_sys::HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+    web_sys::Url::revoke_object_url(&url)?;
+    Ok(())
+}
+
+/// Start the background loop that feeds received frames into the shared
+/// log, along with the buffer drain. `active` asks the loop to keep going;
+/// `running` is owned by the loop itself, so a stop quickly followed by a
+/// start reuses the loop that is still winding down instead of spawning a
+/// second one.
+fn start_read_loop(
+    serial: &SerialManager,
+    active: &Rc<RefCell<bool>>,
+    running: &Rc<RefCell<bool>>,
+    last_rx: &UseStateHandle<String>,
+) {
+    *active.borrow_mut() = true;
+    serial.spawn_buffer_drain();
+    if *running.borrow() {
+        return;
+    }
+    *running.borrow_mut() = true;
+    let serial = serial.clone();
+    let active = active.clone();
+    let running = running.clone();
+    let last_rx = last_rx.clone();
+    spawn_local(async move {
+        while *active.borrow() {
+            if let Ok(frames) = serial.read_all_frames().await {
+                for resp in frames {
+                    serial.log_rx(resp.clone());
+                    last_rx.set(resp);
+                }
+            }
+            TimeoutFuture::new(200).await;
+        }
+        *running.borrow_mut() = false;
+    });
+}
+
+/// Ask the read loop and drain to stop, then release the reader lock.
+fn stop_read_loop(serial: &SerialManager, active: &Rc<RefCell<bool>>) {
+    *active.borrow_mut() = false;
+    serial.stop_buffer_drain();
+    let serial = serial.clone();
+    spawn_local(async move {
+        let _ = serial.stop_reader().await;
+    });
+}
+
+/// What the per-row copy button puts on the clipboard: the frequency as
+/// shown in the table, plus mode and callsign when `details` is set.
+fn spot_copy_text(spot: &Spot, details: bool) -> String {
+    if details {
+        format!("{:.4} {} {}", spot.frequency_mhz, spot.mode, spot.callsign)
+    } else {
+        format!("{:.4}", spot.frequency_mhz)
+    }
+}
+
+/// Write `text` to the system clipboard.
+async fn copy_text(text: &str) -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("no window"))?;
+    let promise = window.navigator().clipboard().write_text(text);
+    wasm_bindgen_futures::JsFuture::from(promise).await?;
+    Ok(())
+}
+
+/// Summarize the radio's RX/TX VFOs, e.g.
+/// "RX A 14.062000 MHz · TX B 14.063000 MHz · split on".
+fn format_split(state: &SplitState) -> String {
+    let vfo = |v: Option<u8>| match v {
+        Some(0) => "A",
+        Some(1) => "B",
+        _ => "?",
+    };
+    let mhz = |hz: Option<u64>| {
+        hz.map(|hz| format!("{:.6} MHz", hz as f64 / 1_000_000.0))
+            .unwrap_or_else(|| "unknown".to_string())
+    };
+    let split = match state.is_split() {
+        Some(true) => "split on",
+        Some(false) => "split off",
+        None => "split status not reported",
+    };
+    format!(
+        "RX {} {} · TX {} {} · {}",
+        vfo(state.rx_vfo),
+        mhz(state.rx_hz().or(state.vfo_a_hz)),
+        vfo(state.tx_vfo),
+        mhz(state.tx_hz().or(state.vfo_b_hz)),
+        split
+    )
+}
+
+/// Render an age in milliseconds as "just now", "12m" or "2h 05m".
+fn format_age(age_ms: f64) -> String {
+    let minutes = (age_ms / 60_000.0).floor().max(0.0) as u64;
+    match minutes {
+        0 => "just now".to_string(),
+        m if m < 60 => format!("{}m", m),
+        m => format!("{}h {:02}m", m / 60, m % 60),
+    }
+}
+
+/// Connection uptime as H:MM:SS.
+fn format_uptime(elapsed_ms: f64) -> String {
+    let secs = (elapsed_ms / 1000.0).floor().max(0.0) as u64;
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Ask the radio which mode it's in, as a label like "CW". Errors and
+/// unknown codes come back as `None`; this is informational only.
+async fn read_radio_mode(serial: &SerialManager) -> Option<&'static str> {
+    let frame = KenwoodDriver::query_mode(serial).await.ok()?;
+    KenwoodDriver::parse_mode(&frame).and_then(KenwoodDriver::mode_label)
+}
+
+/// Read back the radio's frequency and mode after a tune. Returns a warning
+/// message when either differs from what was requested.
+async fn verify_tune(serial: &SerialManager, freq_hz: u64, mode: &str) -> Result<Option<String>, JsValue> {
+    let freq_frame = KenwoodDriver::query_frequency(serial).await?;
+    let reported_hz = KenwoodDriver::parse_frequency(&freq_frame)
+        .ok_or_else(|| JsValue::from_str(&format!("unexpected FA reply: {}", freq_frame)))?;
+    if reported_hz.abs_diff(freq_hz) > VERIFY_TOLERANCE_HZ {
+        return Ok(Some(format!(
+            "Tune mismatch: requested {}, radio reports {}",
+            freq_hz as f64 / 1_000_000.0,
+            reported_hz as f64 / 1_000_000.0
+        )));
+    }
+
+    let mode_frame = KenwoodDriver::query_mode(serial).await?;
+    let reported_mode = KenwoodDriver::parse_mode(&mode_frame)
+        .ok_or_else(|| JsValue::from_str(&format!("unexpected MD reply: {}", mode_frame)))?;
+    if Some(reported_mode) != KenwoodDriver::expected_mode_code(mode) {
+        let reported = KenwoodDriver::mode_label(reported_mode)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("MD{}", reported_mode));
+        return Ok(Some(format!(
+            "Tune mismatch: requested {}, radio reports {}",
+            mode, reported
+        )));
+    }
+    Ok(None)
+}
+
+/// What a screen reader announces for a spot row, which tunes on Enter.
+fn spot_aria_label(spot: &Spot) -> String {
+    let mut label = format!(
+        "Tune to {} on {}, {:.4} MHz",
+        spot.callsign, spot.summit, spot.frequency_mhz
+    );
+    if !spot.mode.trim().is_empty() {
+        label.push(' ');
+        label.push_str(spot.mode.trim());
+    }
+    label
+}
+
+/// Whether `key` (a `KeyboardEvent::key` value) tunes a focused spot row,
+/// as Enter and Space do for a button.
+fn activates_row(key: &str) -> bool {
+    matches!(key, "Enter" | " ")
+}
+
+/// Whether a key event is aimed at a form control, button, link or editable
+/// text, so table navigation leaves it alone. Enter on a focused button
+/// presses that button; it mustn't also tune the highlighted row.
+fn is_typing_target(event: &KeyboardEvent) -> bool {
+    event
+        .target()
+        .and_then(|t| t.dyn_into::<web_sys::HtmlElement>().ok())
+        .is_some_and(|el| {
+            matches!(el.tag_name().as_str(), "INPUT" | "SELECT" | "TEXTAREA" | "BUTTON" | "A")
+                || el.is_content_editable()
+        })
+}
+
+/// Format epoch milliseconds as local HH:MM.
+fn format_clock(ms: f64) -> String {
+    let date = js_sys::Date::new(&JsValue::from_f64(ms));
+    format!("{:02}:{:02}", date.get_hours(), date.get_minutes())
+}
+
+fn format_time(ts: &str) -> String {
+    // Expecting ISO-like timestamp. Extract HH:MM:SS(.sss) if present.
+    if let Some(t_pos) = ts.find('T') {
+        let time_part = &ts[t_pos + 1..];
+        let trimmed = time_part
+            .trim_end_matches('Z')
+            .split(['.', '+', '-'])
+            .next()
+            .unwrap_or(time_part);
+        return trimmed.to_string();
+    }
+    ts.to_string()
+}
+
+#[function_component(App)]
+pub fn app() -> Html {
+    let initial_cache = use_memo((), |_| load_spots_cache());
+    let spots = {
+        let initial_cache = initial_cache.clone();
+        use_state(move || (*initial_cache).as_ref().map(|c| c.spots.clone()).unwrap_or_default())
+    };
+    let cached_at = use_state(|| (*initial_cache).as_ref().map(|c| c.saved_ms));
+    let spots_loaded = use_state(|| initial_cache.is_some());
+    let selected_row = use_state(|| None::<usize>);
+    // Row under the keyboard cursor, separate from the last tuned row.
+    let highlighted = use_state(|| None::<usize>);
+    let storage_warning = use_state(|| !settings::available());
+    let page = use_state(|| 0_usize);
+    // The list the row indices above last referred to, so a refresh can
+    // carry them over to the new list.
+    let indexed_spots = use_mut_ref(Vec::<Spot>::new);
+    // Set when a refresh moves the highlight, so the move doesn't scroll
+    // the table out from under the user.
+    let skip_highlight_scroll = use_mut_ref(|| false);
+    // Rebuilt every render so the window key listener always sees current
+    // state; the listener itself is registered once.
+    let key_handler = use_mut_ref(Callback::<KeyboardEvent>::noop);
+    // Active sort column and whether it's ascending; `None` keeps API order.
+    let sort = use_state(|| None::<(SortColumn, bool)>);
+    let status = use_status();
+    let connected = use_state(|| false);
+    let serial = use_state(SerialManager::new);
+    let baud_rate = use_state(load_baud_rate);
+    let rig = use_state(|| {
+        settings::get::<String>(STORAGE_RIG)
+            .and_then(|k| Rig::from_key(&k))
+            .unwrap_or(Rig::Kenwood)
+    });
+    let kenwood_model = use_state(|| {
+        settings::get::<String>(STORAGE_KENWOOD_MODEL)
+            .and_then(|key| KenwoodModel::from_key(&key))
+            .unwrap_or_default()
+    });
+    let split_enabled = use_state(|| settings::get_or(STORAGE_SPLIT, true));
+    let verify_tune_enabled = use_state(|| settings::get_or(STORAGE_VERIFY_TUNE, false));
+    let digital_dial_enabled = use_state(|| settings::get_or(STORAGE_DIGITAL_DIAL, true));
+    let tune_filter_enabled = use_state(|| settings::get_or(STORAGE_TUNE_FILTER, false));
+    let cw_width = use_state(|| settings::get_or(STORAGE_CW_WIDTH, DEFAULT_CW_WIDTH_HZ as f64).round() as u32);
+    let ssb_width = use_state(|| settings::get_or(STORAGE_SSB_WIDTH, DEFAULT_SSB_WIDTH_HZ as f64).round() as u32);
+    let tune_filter = (*tune_filter_enabled).then_some(TuneFilter { cw_hz: *cw_width, ssb_hz: *ssb_width });
+    let watchlist = use_state(|| settings::get::<String>(STORAGE_WATCHLIST).unwrap_or_default());
+    let my_summit = use_state(|| settings::get::<String>(STORAGE_MY_SUMMIT).unwrap_or_default());
+    let my_grid = use_state(|| settings::get::<String>(STORAGE_MY_GRID).unwrap_or_default());
+    let s2s_only = use_state(|| settings::get_or(STORAGE_S2S_ONLY, false));
+    let watch_beep = use_state(|| settings::get_or(STORAGE_WATCH_BEEP, false));
+    let alert_muted = use_state(|| settings::get_or(STORAGE_ALERT_MUTED, false));
+    // Spots present in the previous refresh; `None` until the first one.
+    let previous_spots = use_mut_ref(|| None::<Vec<Spot>>);
+    let stale_minutes = use_state(|| settings::get_or(STORAGE_STALE_MINUTES, DEFAULT_STALE_MINUTES));
+    let clash_khz = use_state(|| settings::get_or(STORAGE_CLASH_KHZ, DEFAULT_CLASH_KHZ));
+    let min_freq = use_state(|| 7.0_f64);
+    let max_freq = use_state(|| 29.7_f64);
+    let band_filter = use_state(|| None::<String>);
+    let mode_filter = use_state(Vec::<String>::new);
+    let region_filter = use_state(|| {
+        settings::get::<String>(STORAGE_REGIONS)
+            .unwrap_or_default()
+            .split(',')
+            .filter(|r| REGIONS.iter().any(|(name, _)| name == r))
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    });
+    let association_filter = use_state(|| settings::get::<String>(STORAGE_ASSOCIATIONS).unwrap_or_default());
+    let comment_filter = use_state(|| settings::get::<String>(STORAGE_COMMENT_FILTER).unwrap_or_default());
+    let favorites = use_state(load_favorites);
+    let favorites_only = use_state(|| settings::get_or(STORAGE_FAVORITES_ONLY, false));
+    let compact = use_state(|| settings::get_or(STORAGE_COMPACT, false));
+    let compact_hide_comments = use_state(|| settings::get_or(STORAGE_COMPACT_HIDE_COMMENTS, true));
+    let hidden_columns = use_state(load_hidden_columns);
+    let worked = use_state(load_worked);
+    // Read by the refresh interval, which outlives any single render.
+    let spot_count = use_state(load_spot_count);
+    let spots_api = use_state(load_spots_api);
+    let sources = use_mut_ref(|| build_sources(&load_spots_api(), load_spot_count()));
+    let spots_epoch = use_mut_ref(|| None::<String>);
+    let raw_response = use_mut_ref(|| None::<String>);
+    let dedup_minutes = use_mut_ref(|| settings::get_or(STORAGE_DEDUP_MINUTES, DEFAULT_DEDUP_MINUTES));
+    let summits: SummitCache = use_mut_ref(HashMap::new);
+    let redraw = use_force_update();
+    let next_refresh_ms = use_mut_ref(|| 0.0_f64);
+    let retry_attempt = use_mut_ref(|| 0_usize);
+    let refresh_secs = use_state(load_refresh_secs);
+    let refresh_secs_ref = use_mut_ref(|| *refresh_secs);
+    let countdown_secs = use_state(|| *refresh_secs);
+    let show_settings = use_state(|| false);
+    let show_self_spot = use_state(|| false);
+    let self_spot_form = use_state(SelfSpotForm::default);
+    // Deliberately not persisted: settings export would write it to disk.
+    let spot_token = use_state(String::new);
+    let self_spot_sending = use_state(|| false);
+    let self_spot_reply = use_state(|| None::<Result<String, String>>);
+    let show_onboarding = use_state(|| !settings::get_or(STORAGE_ONBOARDED, false));
+    let raw_cmd = use_state(|| "".to_string());
+    // Sends the raw field untouched; deliberately not persisted.
+    let raw_force = use_state(|| false);
+    let cat_macro = use_state(|| settings::get::<String>(STORAGE_CAT_MACRO).unwrap_or_default());
+    let tuned_contacts = use_mut_ref(Vec::<TunedContact>::new);
+    // Recently tuned (MHz, mode), newest first. Written from tune tasks, so
+    // it lives in a ref and redraws explicitly.
+    let tune_history = use_mut_ref(Vec::<(f64, String)>::new);
+    let manual_freq = use_state(String::new);
+    // Empty means "the band's last-used mode".
+    let manual_mode = use_state(String::new);
+    let band_mode_memory = use_state(|| settings::get_or(STORAGE_BAND_MODE_MEMORY, true));
+    // Most recent tuned frequency, so a bare mode change can be credited
+    // to a band.
+    let last_tuned_mhz = use_mut_ref(|| None::<f64>);
+    let log_tx = use_state(|| settings::get_or(STORAGE_LOG_TX, true));
+    let command_delay = use_state(load_command_delay);
+    let last_rx = use_state(|| "".to_string());
+    let smeter = use_state(|| None::<u16>);
+    let split_state = use_state(|| None::<SplitState>);
+    let radio_mode = use_state(|| None::<&'static str>);
+    let poll_tx = use_state(|| settings::get_or(STORAGE_POLL_TX, false));
+    let on_air = use_state(|| false);
+    let rit_offset = use_state(|| 0_i32);
+    // Where the clarifier has been told to go, updated as each step is
+    // queued so quick clicks pulse from the right place.
+    let rit_target = use_mut_ref(|| 0_i32);
+    let filter_width = use_state(|| None::<u32>);
+    let keyer_wpm = use_state(|| 20_u32);
+    // Last known noise blanker / reduction state; `None` until set or read.
+    let noise_blanker = use_state(|| None::<bool>);
+    let noise_reduction = use_state(|| None::<u8>);
+    let agc = use_state(|| None::<AgcSpeed>);
+    // Last known on/standby state from `PS;`; `None` until asked.
+    let radio_power = use_state(|| None::<bool>);
+    let rit_enabled = use_state(|| false);
+    // Tracks whether the settings read loop should keep running. A state
+    // handle captured by the loop would only ever see its initial value.
+    let reader_active = use_mut_ref(|| false);
+    // Set by the read loop while it runs; see `start_read_loop`.
+    let reader_running = use_mut_ref(|| false);
+    let session_mode = use_state(|| settings::get_or(STORAGE_SESSION_MODE, false));
+    let tuning_locked = use_state(|| settings::get_or(STORAGE_TUNING_LOCKED, false));
+    // Deliberately not persisted: every visit starts with TX off.
+    let tx_enabled = use_state(|| false);
+    // Also off on every visit; tunes new matching spots when armed with TX on.
+    let auto_tune = use_state(|| false);
+    // Every spot key shown so far, so nothing already listed when auto-tune
+    // is armed, or tuned once, is auto-tuned again.
+    let auto_tune_seen = use_mut_ref(HashSet::<String>::new);
+    let copy_details = use_state(|| settings::get_or(STORAGE_COPY_DETAILS, false));
+    let show_map = use_state(|| settings::get_or(STORAGE_SHOW_MAP, false));
+    let map_tunes = use_state(|| settings::get_or(STORAGE_MAP_TUNES, false));
+    let segment_rules_text = use_state(|| settings::get::<String>(STORAGE_SEGMENT_RULES).unwrap_or_default());
+    let segment_rules = use_state(|| parse_segment_rules(&segment_rules_text).unwrap_or_default());
+
+    {
+        let min_freq = min_freq.clone();
+        let max_freq = max_freq.clone();
+        let band_filter = band_filter.clone();
+        let mode_filter = mode_filter.clone();
+        use_effect_with((), move |_| {
+            min_freq.set(settings::get_or(STORAGE_MIN_FREQ, 7.0));
+            max_freq.set(settings::get_or(STORAGE_MAX_FREQ, 29.7));
+            band_filter.set(
+                settings::get::<String>(STORAGE_BAND).filter(|b| BANDS.iter().any(|(label, _, _)| label == b)),
+            );
+            mode_filter.set(
+                settings::get::<String>(STORAGE_MODES)
+                    .unwrap_or_default()
+                    .split(',')
+                    .filter(|m| MODE_GROUPS.contains(m))
+                    .map(str::to_string)
+                    .collect(),
+            );
+            || ()
+        });
+    }
+
+    let fetch_ctx = FetchContext {
+        spots: spots.clone(),
+        status: status.clone(),
+        sources: sources.clone(),
+        epoch: spots_epoch.clone(),
+        dedup_minutes: dedup_minutes.clone(),
+        summits: summits.clone(),
+        redraw: redraw.clone(),
+        next_refresh_ms: next_refresh_ms.clone(),
+        refresh_secs: refresh_secs_ref.clone(),
+        retry_attempt: retry_attempt.clone(),
+        cached_at: cached_at.clone(),
+        loaded: spots_loaded.clone(),
+        raw_response: raw_response.clone(),
+    };
+
+    {
+        let fetch_ctx = fetch_ctx.clone();
+        let countdown_secs = countdown_secs.clone();
+        use_effect_with(*refresh_secs, move |&secs| {
+            *fetch_ctx.refresh_secs.borrow_mut() = secs;
+            if *fetch_ctx.next_refresh_ms.borrow() == 0.0 {
+                fetch_spots(fetch_ctx.clone());
             } else {
-                // closing settings: stop the background reader and keep port open
-                show_settings.set(false);
-                // stop drain immediately, then cancel the reader
-                serial.stop_buffer_drain();
-                let serial = serial.clone();
-                spawn_local(async move {
-                    let _ = serial.stop_reader().await;
-                });
+                // Interval changed: count the new one down from now.
+                *fetch_ctx.next_refresh_ms.borrow_mut() = js_sys::Date::now() + secs as f64 * 1000.0;
             }
+            // Tick every second: update the countdown and refresh once the
+            // deadline passes. Every fetch, manual or automatic, pushes the
+            // deadline out again.
+            let interval = Interval::new(1000, move || {
+                let remaining_ms = *fetch_ctx.next_refresh_ms.borrow() - js_sys::Date::now();
+                if remaining_ms <= 0.0 {
+                    fetch_spots(fetch_ctx.clone());
+                    countdown_secs.set(*fetch_ctx.refresh_secs.borrow());
+                } else {
+                    countdown_secs.set((remaining_ms / 1000.0).ceil() as u32);
+                }
+            });
+            move || drop(interval)
+        });
+    }
+
+    {
+        let watchlist = (*watchlist).clone();
+        let watch_beep = *watch_beep;
+        let alert_muted = *alert_muted;
+        let band_filter = (*band_filter).clone();
+        let mode_filter = (*mode_filter).clone();
+        let region_filter = (*region_filter).clone();
+        let association_filter = (*association_filter).clone();
+        let comment_filter = (*comment_filter).clone();
+        let previous_spots = previous_spots.clone();
+        use_effect_with(spots.clone(), move |spots| {
+            let previous = previous_spots.borrow_mut().replace((**spots).clone());
+            if let Some(previous) = previous {
+                let filters = SpotFilters {
+                    band: band_filter.as_deref(),
+                    modes: &mode_filter,
+                    regions: &region_filter,
+                    associations: &association_filter,
+                    comments: &comment_filter,
+                };
+                let calls = previous.iter().map(|s| s.callsign.to_uppercase()).collect::<Vec<_>>();
+                let newly_watched = spots.iter().any(|s| {
+                    !calls.contains(&s.callsign.to_uppercase()) && is_watched(&watchlist, &s.callsign)
+                });
+                let new_match = spots.iter().any(|s| {
+                    !previous.iter().any(|p| p.same_spot(s))
+                        && filters.matches(s)
+                });
+                if (watch_beep && newly_watched) || (!alert_muted && new_match) {
+                    play_beep();
+                }
+            }
+            || ()
+        });
+    }
+
+    {
+        let serial = serial.clone();
+        let connected = connected.clone();
+        let status = status.clone();
+        let baud_rate = *baud_rate;
+        let session_mode = *session_mode;
+        let reader_active = reader_active.clone();
+        let reader_running = reader_running.clone();
+        let last_rx = last_rx.clone();
+        let rig = *rig;
+        let radio_mode = radio_mode.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                match serial.try_reconnect(baud_rate).await {
+                    Ok(true) => {
+                        connected.set(true);
+                        status.push_status(StatusLevel::Success, "Serial reconnected");
+                        if rig == Rig::Kenwood {
+                            radio_mode.set(read_radio_mode(&serial).await);
+                        }
+                        if session_mode {
+                            start_read_loop(&serial, &reader_active, &reader_running, &last_rx);
+                        }
+                    }
+                    Ok(false) => {}
+                    Err(err) => {
+                        // Leave the manual Connect button as the fallback.
+                        web_sys::console::log_1(&JsValue::from_str(&format!(
+                            "app: serial reconnect skipped: {:?}",
+                            err
+                        )));
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    {
+        let serial = serial.clone();
+        let connected = connected.clone();
+        let status = status.clone();
+        let reader_active = reader_active.clone();
+        use_effect_with((), move |_| {
+            serial.set_on_disconnect(move || {
+                *reader_active.borrow_mut() = false;
+                connected.set(false);
+                status.push_status(StatusLevel::Error, "Radio disconnected");
+            });
+            || ()
+        });
+    }
+
+    {
+        let key_handler = key_handler.clone();
+        use_effect_with((), move |_| {
+            let listener = Closure::<dyn FnMut(KeyboardEvent)>::new(move |event: KeyboardEvent| {
+                let handler = key_handler.borrow().clone();
+                handler.emit(event);
+            });
+            let window = web_sys::window();
+            if let Some(window) = &window {
+                let _ = window
+                    .add_event_listener_with_callback("keydown", listener.as_ref().unchecked_ref());
+            }
+            move || {
+                if let Some(window) = window {
+                    let _ = window.remove_event_listener_with_callback(
+                        "keydown",
+                        listener.as_ref().unchecked_ref(),
+                    );
+                }
+            }
+        });
+    }
+
+    {
+        let selected_row = selected_row.clone();
+        let highlighted = highlighted.clone();
+        let indexed_spots = indexed_spots.clone();
+        let skip_highlight_scroll = skip_highlight_scroll.clone();
+        use_effect_with(spots.clone(), move |spots| {
+            let old = std::mem::replace(&mut *indexed_spots.borrow_mut(), (**spots).clone());
+            if !old.is_empty() {
+                selected_row.set(remap_row(&old, spots, *selected_row));
+                let moved = remap_row(&old, spots, *highlighted);
+                if moved != *highlighted {
+                    *skip_highlight_scroll.borrow_mut() = true;
+                    highlighted.set(moved);
+                }
+            }
+            || ()
+        });
+    }
+
+    let scroll_skip = skip_highlight_scroll.clone();
+    use_effect_with(*highlighted, move |highlighted| {
+        let row = web_sys::window()
+            .and_then(|w| w.document())
+            .zip(*highlighted)
+            .and_then(|(doc, idx)| doc.get_element_by_id(&format!("spot-row-{}", idx)));
+        match row {
+            Some(row) if !scroll_skip.replace(false) => {
+                let options = web_sys::ScrollIntoViewOptions::new();
+                options.set_block(web_sys::ScrollLogicalPosition::Nearest);
+                row.scroll_into_view_with_scroll_into_view_options(&options);
+                // Keep keyboard focus on the highlighted row so arrow-key
+                // moves are announced, but only if focus is already on a row
+                // or nowhere in particular; a refresh mustn't steal it.
+                let focus_free = web_sys::window()
+                    .and_then(|w| w.document())
+                    .and_then(|doc| doc.active_element())
+                    .is_none_or(|el| matches!(el.tag_name().as_str(), "BODY" | "TR"));
+                if focus_free {
+                    if let Ok(row) = row.dyn_into::<web_sys::HtmlElement>() {
+                        let _ = row.focus();
+                    }
+                }
+            }
+            _ => *scroll_skip.borrow_mut() = false,
+        }
+        || ()
+    });
+
+    {
+        let serial = serial.clone();
+        let redraw = redraw.clone();
+        let log_tx = *log_tx;
+        let command_delay = *command_delay;
+        let kenwood_model = *kenwood_model;
+        use_effect_with((), move |_| {
+            serial.set_log_tx(log_tx);
+            serial.set_command_delay(command_delay);
+            serial.set_kenwood_model(kenwood_model);
+            serial.set_on_log(move || redraw.force_update());
+            || ()
+        });
+    }
+
+    {
+        let serial = serial.clone();
+        let on_air = on_air.clone();
+        let polling = *connected && *poll_tx && *rig == Rig::Kenwood;
+        use_effect_with(polling, move |&polling| {
+            // One query at a time: a slow radio shouldn't pile them up.
+            let in_flight = Rc::new(std::cell::Cell::new(false));
+            let interval = polling.then(|| {
+                let on_air = on_air.clone();
+                Interval::new(TX_POLL_MS, move || {
+                    if in_flight.replace(true) {
+                        return;
+                    }
+                    let serial = serial.clone();
+                    let on_air = on_air.clone();
+                    let in_flight = in_flight.clone();
+                    spawn_local(async move {
+                        if let Ok(transmitting) = KenwoodDriver::query_transmitting(&serial).await {
+                            on_air.set(transmitting);
+                        }
+                        in_flight.set(false);
+                    });
+                })
+            });
+            move || {
+                drop(interval);
+                on_air.set(false);
+            }
+        });
+    }
+
+    let on_connect = {
+        let serial = serial.clone();
+        let connected = connected.clone();
+        let status = status.clone();
+        let baud_rate = baud_rate.clone();
+        let rig = rig.clone();
+        let radio_mode = radio_mode.clone();
+        let session_mode = *session_mode;
+        let reader_active = reader_active.clone();
+        let reader_running = reader_running.clone();
+        let last_rx = last_rx.clone();
+        Callback::from(move |_| {
+            let serial = serial.clone();
+            let connected = connected.clone();
+            let status = status.clone();
+            let baud_rate = *baud_rate;
+            let reader_active = reader_active.clone();
+            let reader_running = reader_running.clone();
+            let last_rx = last_rx.clone();
+            let rig = *rig;
+            let radio_mode = radio_mode.clone();
+            spawn_local(async move {
+                status.push_status(StatusLevel::Info, "Requesting serial port...");
+                match serial.connect(baud_rate).await {
+                    Ok(()) => {
+                        connected.set(true);
+                        status.push_status(StatusLevel::Success, "Serial connected");
+                        if rig == Rig::Kenwood {
+                            radio_mode.set(read_radio_mode(&serial).await);
+                        }
+                        if session_mode {
+                            start_read_loop(&serial, &reader_active, &reader_running, &last_rx);
+                        }
+                    }
+                    Err(err) => {
+                        status.push_status(StatusLevel::Error, format!("Serial connect failed: {:?}", err));
+                    }
+                }
+            });
+        })
+    };
+
+    let on_disconnect = {
+        let serial = serial.clone();
+        let connected = connected.clone();
+        let status = status.clone();
+        let reader_active = reader_active.clone();
+        Callback::from(move |_| {
+            let serial = serial.clone();
+            let connected = connected.clone();
+            let status = status.clone();
+            // stop the settings read loop and drain before the reader goes away
+            *reader_active.borrow_mut() = false;
+            serial.stop_buffer_drain();
+            spawn_local(async move {
+                match serial.disconnect().await {
+                    Ok(()) => status.push_status(StatusLevel::Info, "Serial disconnected"),
+                    Err(err) => status.push_status(StatusLevel::Error, format!("Disconnect failed: {:?}", err)),
+                }
+                connected.set(false);
+            });
+        })
+    };
+
+    let on_export_adif = {
+        let tuned_contacts = tuned_contacts.clone();
+        let status = status.clone();
+        Callback::from(move |_| {
+            let contacts = tuned_contacts.borrow();
+            if contacts.is_empty() {
+                status.push_status(StatusLevel::Error, "No tuned spots to export yet");
+                return;
+            }
+            match download_text("sotachaser.adi", "text/plain", &build_adif(&contacts)) {
+                Ok(()) => status.push_status(StatusLevel::Success, format!("Exported {} spots as ADIF", contacts.len())),
+                Err(err) => status.push_status(StatusLevel::Error, format!("ADIF export failed: {:?}", err)),
+            }
+        })
+    };
+
+    let on_raw_response_toggle = {
+        let raw_response = raw_response.clone();
+        let redraw = redraw.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            // Capture starts with the next fetch; turning it off frees the text.
+            *raw_response.borrow_mut() = input.checked().then(String::new);
+            redraw.force_update();
+        })
+    };
+
+    let on_export_settings = {
+        let status = status.clone();
+        Callback::from(move |_| {
+            match download_text("sotachaser-settings.json", "application/json", &settings::export_json()) {
+                Ok(()) => status.push_status(StatusLevel::Success, "Exported settings"),
+                Err(err) => status.push_status(StatusLevel::Error, format!("Settings export failed: {:?}", err)),
+            }
+        })
+    };
+
+    let on_import_settings = {
+        let status = status.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            // Clear the picker so choosing the same file again still fires.
+            input.set_value("");
+            let status = status.clone();
+            spawn_local(async move {
+                let text = match wasm_bindgen_futures::JsFuture::from(file.text()).await {
+                    Ok(text) => text.as_string().unwrap_or_default(),
+                    Err(err) => {
+                        status.push_status(StatusLevel::Error, format!("Could not read settings file: {:?}", err));
+                        return;
+                    }
+                };
+                match settings::import_json(&text, SETTINGS_SCHEMA) {
+                    Ok(0) => status.push_status(StatusLevel::Warning, "No recognised settings in that file"),
+                    Ok(count) => {
+                        status.push_status(StatusLevel::Success, format!("Imported {} settings; reloading", count));
+                        // Every piece of state is seeded from storage on start-up,
+                        // so a reload is the simplest way to apply them all.
+                        Timeout::new(1_000, || {
+                            if let Some(window) = web_sys::window() {
+                                let _ = window.location().reload();
+                            }
+                        })
+                        .forget();
+                    }
+                    Err(err) => status.push_status(StatusLevel::Error, format!("Settings import failed: {}", err)),
+                }
+            });
+        })
+    };
+
+    let on_refresh = {
+        let fetch_ctx = fetch_ctx.clone();
+        let countdown_secs = countdown_secs.clone();
+        Callback::from(move |_| {
+            fetch_spots(fetch_ctx.clone());
+            countdown_secs.set(*fetch_ctx.refresh_secs.borrow());
+        })
+    };
+
+    let on_tune = {
+        let serial = serial.clone();
+        let segment_rules = segment_rules.clone();
+        let selected_row = selected_row.clone();
+        let status = status.clone();
+        let connected = connected.clone();
+        let spots = spots.clone();
+        let min_freq = min_freq.clone();
+        let max_freq = max_freq.clone();
+        let split_enabled = split_enabled.clone();
+        let verify_tune_enabled = verify_tune_enabled.clone();
+        let digital_dial_enabled = digital_dial_enabled.clone();
+        let band_mode_memory = band_mode_memory.clone();
+        let last_tuned_mhz = last_tuned_mhz.clone();
+        let rig = rig.clone();
+        let tuned_contacts = tuned_contacts.clone();
+        let tune_history = tune_history.clone();
+        let redraw = redraw.clone();
+        let tuning_locked = *tuning_locked;
+        Callback::from(move |row: usize| {
+            if tuning_locked {
+                status.push_status(StatusLevel::Warning, TUNING_LOCKED);
+                return;
+            }
+            if !*connected {
+                status.push_status(StatusLevel::Error, "Connect serial first".to_string());
+                return;
+            }
+            let Some(busy) = serial.try_begin_tune() else {
+                status.push_status(StatusLevel::Info, TUNE_BUSY);
+                return;
+            };
+
+            let serial = serial.clone();
+            let selected_row = selected_row.clone();
+            let status = status.clone();
+            let spots = spots.clone();
+            let min_freq = *min_freq;
+            let max_freq = *max_freq;
+            let segment_rules = (*segment_rules).clone();
+            let split_enabled = *split_enabled;
+            let verify_tune_enabled = *verify_tune_enabled;
+            let digital_dial_enabled = *digital_dial_enabled;
+            let band_mode_memory = *band_mode_memory;
+            let tune_filter = tune_filter;
+            let last_tuned_mhz = last_tuned_mhz.clone();
+            let rig = *rig;
+            let tuned_contacts = tuned_contacts.clone();
+            let tune_history = tune_history.clone();
+            let redraw = redraw.clone();
+            spawn_local(async move {
+                let _busy = busy;
+                if let Some(spot) = spots.get(row) {
+                    let dial_mhz = if digital_dial_enabled {
+                        digital_dial(&spot.mode, spot.frequency_mhz)
+                    } else {
+                        None
+                    };
+                    let tune_mhz = dial_mhz.unwrap_or(spot.frequency_mhz);
+                    if let Err(msg) = check_tune_guard(tune_mhz, min_freq, max_freq)
+                        .and_then(|()| check_segment_rules(&segment_rules, tune_mhz, &spot.mode))
+                    {
+                        status.push_status(StatusLevel::Error, msg);
+                        return;
+                    }
+                    let freq_hz = (tune_mhz * 1_000_000.0).round() as u64;
+                    // Split and read-back are Kenwood-only for now.
+                    let kenwood = rig == Rig::Kenwood;
+                    let split_mhz = if split_enabled && kenwood && dial_mhz.is_none() {
+                        parse_split_hint(&spot.comments, spot.frequency_mhz)
+                    } else {
+                        None
+                    };
+                    // Setting up split can key the radio; without TX the
+                    // receive side is still worth tuning.
+                    let split_mhz = split_mhz.filter(|_| {
+                        let allowed = serial.tx_enabled();
+                        if !allowed {
+                            status.push_status(
+                                StatusLevel::Warning,
+                                format!("Split skipped, tuning RX only: {}", TX_DISABLED),
+                            );
+                        }
+                        allowed
+                    });
+                    let result = match split_mhz {
+                        Some(tx_mhz) => {
+                            if let Err(msg) = check_tune_guard(tx_mhz, min_freq, max_freq)
+                                .and_then(|()| check_segment_rules(&segment_rules, tx_mhz, &spot.mode))
+                            {
+                                status.push_status(StatusLevel::Error, format!("Split TX {}", msg));
+                                return;
+                            }
+                            status.push_status(StatusLevel::Info, format!(
+                                "Tuning {} MHz {}, split {:+.3} kHz (TX {:.4} MHz)",
+                                spot.frequency_mhz,
+                                spot.mode,
+                                (tx_mhz - spot.frequency_mhz) * 1000.0,
+                                tx_mhz
+                            ));
+                            let tx_hz = (tx_mhz * 1_000_000.0).round() as u64;
+                            serial.tune_kenwood_ts570_split(freq_hz, tx_hz, &spot.mode).await
+                        }
+                        None => {
+                            match dial_mhz {
+                                Some(dial) => status.push_status(StatusLevel::Info, format!(
+                                    "Tuning {} dial {} MHz USB (spotted {} MHz)",
+                                    spot.mode, dial, spot.frequency_mhz
+                                )),
+                                None => status.push_status(StatusLevel::Info, format!("Tuning {} MHz {}", spot.frequency_mhz, spot.mode)),
+                            }
+                            rig.driver().tune(&serial, freq_hz, &spot.mode).await
+                        }
+                    };
+                    match result {
+                        Ok(()) => {
+                            selected_row.set(Some(row));
+                            *last_tuned_mhz.borrow_mut() = Some(tune_mhz);
+                            if band_mode_memory {
+                                remember_band_mode(tune_mhz, &spot.mode);
+                            }
+                            push_tune_history(&mut tune_history.borrow_mut(), tune_mhz, &spot.mode);
+                            redraw.force_update();
+                            tuned_contacts.borrow_mut().push(TunedContact {
+                                callsign: spot.callsign.clone(),
+                                summit: spot.summit.clone(),
+                                frequency_mhz: spot.frequency_mhz,
+                                mode: spot.mode.clone(),
+                                tuned_at_ms: js_sys::Date::now(),
+                            });
+                            match split_mhz {
+                                Some(tx_mhz) => status.push_status(StatusLevel::Success, format!("Tuned split, TX {:.4} MHz", tx_mhz)),
+                                None => status.push_status(StatusLevel::Success, "Tuned"),
+                            }
+                            if let Some(filter) = tune_filter.filter(|_| kenwood) {
+                                if let Err(err) = apply_tune_filter(&serial, &spot.mode, filter).await {
+                                    status.push_status(StatusLevel::Error, format!("Set width failed: {:?}", err));
+                                }
+                            }
+                            if verify_tune_enabled && kenwood {
+                                match verify_tune(&serial, freq_hz, &spot.mode).await {
+                                    Ok(None) => status.push_status(StatusLevel::Success, "Tuned and verified"),
+                                    Ok(Some(warning)) => status.push_status(StatusLevel::Error, warning),
+                                    Err(err) => status.push_status(StatusLevel::Error, format!("Tune verify failed: {:?}", err)),
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            status.push_status(StatusLevel::Error, format!("Tune failed: {:?}", err));
+                        }
+                    }
+                }
+            });
+        })
+    };
+
+    // Auto-tune: hand the newest unseen matching spot to `on_tune` once.
+    {
+        let on_tune = on_tune.clone();
+        let status = status.clone();
+        let armed = *auto_tune && *tx_enabled;
+        let band_filter = (*band_filter).clone();
+        let mode_filter = (*mode_filter).clone();
+        let region_filter = (*region_filter).clone();
+        let association_filter = (*association_filter).clone();
+        let comment_filter = (*comment_filter).clone();
+        let auto_tune_seen = auto_tune_seen.clone();
+        use_effect_with(spots.clone(), move |spots| {
+            let mut seen = auto_tune_seen.borrow_mut();
+            let pick = if armed {
+                let filters = SpotFilters {
+                    band: band_filter.as_deref(),
+                    modes: &mode_filter,
+                    regions: &region_filter,
+                    associations: &association_filter,
+                    comments: &comment_filter,
+                };
+                pick_auto_tune(spots, &seen, |spot| filters.matches(spot))
+            } else {
+                None
+            };
+            seen.extend(spots.iter().map(Spot::tune_key));
+            drop(seen);
+            if let Some(idx) = pick {
+                let spot = &spots[idx];
+                // `on_tune` can still refuse (locked, busy, disconnected)
+                // and says why, so this only announces the attempt.
+                status.push_status(
+                    StatusLevel::Warning,
+                    format!("Auto-tune: trying new spot {} {} {:.4}", spot.callsign, spot.summit, spot.frequency_mhz),
+                );
+                on_tune.emit(idx);
+            }
+            || ()
+        });
+    }
+
+    // Tune to an arbitrary frequency and mode, applying the same checks as a
+    // spot click. Used by manual entry and comment QSX links.
+    let tune_to = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let connected = connected.clone();
+        let min_freq = min_freq.clone();
+        let max_freq = max_freq.clone();
+        let segment_rules = segment_rules.clone();
+        let rig = rig.clone();
+        let band_mode_memory = band_mode_memory.clone();
+        let last_tuned_mhz = last_tuned_mhz.clone();
+        let tune_history = tune_history.clone();
+        let redraw = redraw.clone();
+        let tuning_locked = *tuning_locked;
+        Callback::from(move |(mhz, mode): (f64, String)| {
+            if tuning_locked {
+                status.push_status(StatusLevel::Warning, TUNING_LOCKED);
+                return;
+            }
+            if !*connected {
+                status.push_status(StatusLevel::Error, "Connect serial first");
+                return;
+            }
+            if !(RADIO_MIN_MHZ..=RADIO_MAX_MHZ).contains(&mhz) {
+                status.push_status(
+                    StatusLevel::Error,
+                    format!("{} MHz is outside the radio's {}–{} MHz range", mhz, RADIO_MIN_MHZ, RADIO_MAX_MHZ),
+                );
+                return;
+            }
+            if let Err(msg) = check_tune_guard(mhz, *min_freq, *max_freq)
+                .and_then(|()| check_segment_rules(&segment_rules, mhz, &mode))
+            {
+                status.push_status(StatusLevel::Error, msg);
+                return;
+            }
+            let Some(busy) = serial.try_begin_tune() else {
+                status.push_status(StatusLevel::Info, TUNE_BUSY);
+                return;
+            };
+
+            let serial = serial.clone();
+            let status = status.clone();
+            let rig = *rig;
+            let band_mode_memory = *band_mode_memory;
+            let last_tuned_mhz = last_tuned_mhz.clone();
+            let tune_history = tune_history.clone();
+            let redraw = redraw.clone();
+            spawn_local(async move {
+                let _busy = busy;
+                let freq_hz = (mhz * 1_000_000.0).round() as u64;
+                let kenwood = rig == Rig::Kenwood;
+                status.push_status(StatusLevel::Info, format!("Tuning {} MHz {}", mhz, mode));
+                match rig.driver().tune(&serial, freq_hz, &mode).await {
+                    Ok(()) => {
+                        *last_tuned_mhz.borrow_mut() = Some(mhz);
+                        if band_mode_memory {
+                            remember_band_mode(mhz, &mode);
+                        }
+                        push_tune_history(&mut tune_history.borrow_mut(), mhz, &mode);
+                        redraw.force_update();
+                        status.push_status(StatusLevel::Success, "Tuned");
+                        if let Some(filter) = tune_filter.filter(|_| kenwood) {
+                            if let Err(err) = apply_tune_filter(&serial, &mode, filter).await {
+                                status.push_status(StatusLevel::Error, format!("Set width failed: {:?}", err));
+                            }
+                        }
+                    }
+                    Err(err) => status.push_status(StatusLevel::Error, format!("Tune failed: {:?}", err)),
+                }
+            });
+        })
+    };
+
+    let on_manual_tune = {
+        let status = status.clone();
+        let manual_freq = manual_freq.clone();
+        let manual_mode = manual_mode.clone();
+        let band_mode_memory = band_mode_memory.clone();
+        let tune_to = tune_to.clone();
+        Callback::from(move |_| {
+            match parse_decimal(&manual_freq) {
+                Some(mhz) if mhz > 0.0 => {
+                    let mode = if !manual_mode.is_empty() {
+                        (*manual_mode).clone()
+                    } else {
+                        band_for_freq(mhz)
+                            .filter(|_| *band_mode_memory)
+                            .and_then(|band| load_band_modes().remove(band))
+                            .unwrap_or_else(|| DEFAULT_MANUAL_MODE.to_string())
+                    };
+                    tune_to.emit((mhz, mode));
+                }
+                _ => status.push_status(StatusLevel::Error, "Enter a positive frequency in MHz"),
+            }
+        })
+    };
+
+    let on_manual_freq_input = {
+        let manual_freq = manual_freq.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            manual_freq.set(input.value());
+        })
+    };
+
+    let on_manual_mode_change = {
+        let manual_mode = manual_mode.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            manual_mode.set(select.value());
+        })
+    };
+
+    let on_dismiss_onboarding = {
+        let show_onboarding = show_onboarding.clone();
+        Callback::from(move |_| {
+            show_onboarding.set(false);
+            settings::set(STORAGE_ONBOARDED, true);
+        })
+    };
+
+    let on_toggle_tx = {
+        let tx_enabled = tx_enabled.clone();
+        let serial = serial.clone();
+        Callback::from(move |_| {
+            let enabled = !*tx_enabled;
+            serial.set_tx_enabled(enabled);
+            tx_enabled.set(enabled);
+        })
+    };
+
+    let on_toggle_auto_tune = {
+        let auto_tune = auto_tune.clone();
+        let tx_enabled = tx_enabled.clone();
+        let status = status.clone();
+        Callback::from(move |_| {
+            let armed = !*auto_tune;
+            if armed && !*tx_enabled {
+                status.push_status(StatusLevel::Warning, "Auto-tune waits until TX is enabled");
+            }
+            auto_tune.set(armed);
+        })
+    };
+
+    let on_toggle_tuning_lock = {
+        let tuning_locked = tuning_locked.clone();
+        Callback::from(move |_| {
+            let locked = !*tuning_locked;
+            tuning_locked.set(locked);
+            settings::set(STORAGE_TUNING_LOCKED, locked);
+        })
+    };
+
+    let on_toggle_self_spot = {
+        let show_self_spot = show_self_spot.clone();
+        let self_spot_form = self_spot_form.clone();
+        let my_summit = my_summit.clone();
+        Callback::from(move |_| {
+            if !*show_self_spot && self_spot_form.summit.is_empty() && !my_summit.is_empty() {
+                let mut form = (*self_spot_form).clone();
+                form.summit = (*my_summit).clone();
+                self_spot_form.set(form);
+            }
+            show_self_spot.set(!*show_self_spot);
+        })
+    };
+
+    let on_self_spot_input = |update: fn(&mut SelfSpotForm, String)| {
+        let self_spot_form = self_spot_form.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut form = (*self_spot_form).clone();
+            update(&mut form, input.value());
+            self_spot_form.set(form);
+        })
+    };
+
+    let on_self_spot_mode_change = {
+        let self_spot_form = self_spot_form.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let mut form = (*self_spot_form).clone();
+            form.mode = select.value();
+            self_spot_form.set(form);
+        })
+    };
+
+    let on_spot_token_input = {
+        let spot_token = spot_token.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            spot_token.set(input.value());
+        })
+    };
+
+    let on_post_self_spot = {
+        let self_spot_form = self_spot_form.clone();
+        let spot_token = spot_token.clone();
+        let spots_api = spots_api.clone();
+        let self_spot_sending = self_spot_sending.clone();
+        let self_spot_reply = self_spot_reply.clone();
+        let status = status.clone();
+        Callback::from(move |_| {
+            let spot = match self_spot_form.validate() {
+                Ok(spot) => spot,
+                Err(err) => {
+                    status.push_status(StatusLevel::Error, err);
+                    return;
+                }
+            };
+            if spot_token.trim().is_empty() {
+                status.push_status(StatusLevel::Error, "Enter your SOTA API token in settings first");
+                return;
+            }
+            self_spot_sending.set(true);
+            self_spot_reply.set(None);
+            let api_base = (*spots_api).clone();
+            let token = (*spot_token).clone();
+            let self_spot_sending = self_spot_sending.clone();
+            let self_spot_reply = self_spot_reply.clone();
+            let status = status.clone();
+            spawn_local(async move {
+                let reply = post_self_spot(&api_base, &token, &spot).await;
+                match &reply {
+                    Ok(_) => status.push_status(
+                        StatusLevel::Success,
+                        format!(
+                            "Spotted {} on {}/{} at {}",
+                            spot.callsign, spot.association, spot.summit, spot.frequency
+                        ),
+                    ),
+                    Err(err) => status.push_status(StatusLevel::Error, format!("Spot not posted: {}", err)),
+                }
+                self_spot_reply.set(Some(reply));
+                self_spot_sending.set(false);
+            });
+        })
+    };
+
+    let on_toggle_settings = {
+        let show_settings = show_settings.clone();
+        let dismiss_onboarding = (*show_onboarding).then(|| on_dismiss_onboarding.clone());
+        let serial = serial.clone();
+        let last_rx = last_rx.clone();
+        let reader_active = reader_active.clone();
+        let reader_running = reader_running.clone();
+        let session_mode = *session_mode;
+        Callback::from(move |_| {
+            if !*show_settings {
+                show_settings.set(true);
+                if let Some(dismiss) = &dismiss_onboarding {
+                    dismiss.emit(());
+                }
+                start_read_loop(&serial, &reader_active, &reader_running, &last_rx);
+            } else {
+                show_settings.set(false);
+                // Session mode keeps responses accumulating while closed.
+                if !session_mode {
+                    stop_read_loop(&serial, &reader_active);
+                }
+            }
+        })
+    };
+
+    let on_session_toggle = {
+        let session_mode = session_mode.clone();
+        let show_settings = *show_settings;
+        let serial = serial.clone();
+        let last_rx = last_rx.clone();
+        let reader_active = reader_active.clone();
+        let reader_running = reader_running.clone();
+        let connected = *connected;
+        Callback::from(move |_: Event| {
+            let value = !*session_mode;
+            settings::set(STORAGE_SESSION_MODE, value);
+            session_mode.set(value);
+            if show_settings {
+                // The settings panel already owns a running loop.
+                return;
+            }
+            if value && connected {
+                start_read_loop(&serial, &reader_active, &reader_running, &last_rx);
+            } else if !value {
+                stop_read_loop(&serial, &reader_active);
+            }
+        })
+    };
+
+    let on_send_raw = {
+        let rig = rig.clone();
+        let raw_cmd = raw_cmd.clone();
+        let raw_force = raw_force.clone();
+        let serial = serial.clone();
+        let status = status.clone();
+        Callback::from(move |_| {
+            let rig = *rig;
+            let cmd = if *raw_force {
+                if raw_cmd.is_empty() {
+                    status.push_status(StatusLevel::Error, "Empty raw command".to_string());
+                    return;
+                }
+                (*raw_cmd).clone()
+            } else {
+                match normalize_raw_command(&raw_cmd) {
+                    Ok(cmd) => {
+                        if !raw_cmd.trim().ends_with(';') {
+                            status.push_status(StatusLevel::Warning, "Added the missing trailing ;");
+                        }
+                        cmd
+                    }
+                    Err(msg) => {
+                        status.push_status(StatusLevel::Error, msg);
+                        return;
+                    }
+                }
+            };
+            let serial = serial.clone();
+            let status = status.clone();
+            spawn_local(async move {
+                match rig.driver().send_raw(&serial, &cmd).await {
+                    Ok(()) => status.push_status(StatusLevel::Info, format!("Sent {:?} ({} bytes)", cmd, cmd.len())),
+                    Err(e) => status.push_status(StatusLevel::Error, format!("Send failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_read_once = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let last_rx = last_rx.clone();
+        Callback::from(move |_| {
+            let serial = serial.clone();
+            let status = status.clone();
+            let last_rx = last_rx.clone();
+            spawn_local(async move {
+                match serial.read_one_frame(READ_ONCE_MS).await {
+                    Ok(Some(frame)) => {
+                        serial.log_rx(frame.clone());
+                        last_rx.set(frame);
+                    }
+                    Ok(None) => status.push_status(StatusLevel::Warning, format!("Nothing received within {} ms", READ_ONCE_MS)),
+                    Err(e) => status.push_status(StatusLevel::Error, format!("Read failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_send_macro = {
+        let cat_macro = cat_macro.clone();
+        let serial = serial.clone();
+        let status = status.clone();
+        let connected = connected.clone();
+        let last_rx = last_rx.clone();
+        Callback::from(move |_| {
+            if !*connected {
+                status.push_status(StatusLevel::Error, "Connect serial first");
+                return;
+            }
+            let commands = match parse_cat_sequence(&cat_macro) {
+                Ok(commands) => commands,
+                Err(msg) => {
+                    status.push_status(StatusLevel::Error, msg);
+                    return;
+                }
+            };
+            let serial = serial.clone();
+            let status = status.clone();
+            let last_rx = last_rx.clone();
+            spawn_local(async move {
+                match serial.send_sequence(&commands).await {
+                    Ok(()) => status.push_status(StatusLevel::Info, format!("Sent {} commands", commands.len())),
+                    Err(e) => {
+                        status.push_status(StatusLevel::Error, format!("Sequence failed: {:?}", e));
+                        return;
+                    }
+                }
+                TimeoutFuture::new(MACRO_REPLY_MS).await;
+                if let Ok(frames) = serial.read_all_frames().await {
+                    for resp in frames {
+                        serial.log_rx(resp.clone());
+                        last_rx.set(resp);
+                    }
+                }
+            });
+        })
+    };
+
+    let on_macro_input = {
+        let cat_macro = cat_macro.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            settings::set(STORAGE_CAT_MACRO, input.value());
+            cat_macro.set(input.value());
+        })
+    };
+
+    let on_segment_rules_input = {
+        let segment_rules_text = segment_rules_text.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: web_sys::HtmlTextAreaElement = e.target_unchecked_into();
+            segment_rules_text.set(input.value());
+        })
+    };
+
+    let on_segment_rules_change = {
+        let segment_rules = segment_rules.clone();
+        let status = status.clone();
+        Callback::from(move |e: Event| {
+            let input: web_sys::HtmlTextAreaElement = e.target_unchecked_into();
+            // Keep the last good rules active until the text parses again.
+            match parse_segment_rules(&input.value()) {
+                Ok(rules) => {
+                    settings::set(STORAGE_SEGMENT_RULES, input.value());
+                    status.push_status(StatusLevel::Info, format!("{} segment rules active", rules.len()));
+                    segment_rules.set(rules);
+                }
+                Err(msg) => status.push_status(StatusLevel::Error, msg),
+            }
+        })
+    };
+
+    let on_raw_force_toggle = {
+        let raw_force = raw_force.clone();
+        Callback::from(move |_: Event| raw_force.set(!*raw_force))
+    };
+
+    let on_raw_input = {
+        let raw_cmd = raw_cmd.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            raw_cmd.set(input.value());
+        })
+    };
+
+    let on_test_14062 = {
+        let serial = serial.clone();
+        let status = status.clone();
+        Callback::from(move |_| {
+            if !serial.tx_enabled() {
+                status.push_status(StatusLevel::Warning, TX_DISABLED);
+                return;
+            }
+            let serial = serial.clone();
+            let status = status.clone();
+            spawn_local(async move {
+                match KenwoodDriver::test_tune(&serial).await {
+                    Ok(()) => status.push_status(StatusLevel::Info, "14.062 CW test tune sent"),
+                    Err(e) => status.push_status(StatusLevel::Error, format!("Test tune failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_atu_tune = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let connected = connected.clone();
+        let min_freq = min_freq.clone();
+        let max_freq = max_freq.clone();
+        Callback::from(move |_| {
+            if !*connected {
+                status.push_status(StatusLevel::Error, "Connect serial first");
+                return;
+            }
+            if !serial.tx_enabled() {
+                status.push_status(StatusLevel::Warning, TX_DISABLED);
+                return;
+            }
+            let confirmed = web_sys::window()
+                .and_then(|w| w.confirm_with_message("Start the antenna tuner? The radio will transmit.").ok())
+                .unwrap_or(false);
+            if !confirmed {
+                return;
+            }
+            let serial = serial.clone();
+            let status = status.clone();
+            let min_freq = *min_freq;
+            let max_freq = *max_freq;
+            spawn_local(async move {
+                // The tuner transmits on whatever the radio is tuned to, so
+                // check that against the guard first.
+                let hz = match KenwoodDriver::query_frequency(&serial).await {
+                    Ok(frame) => KenwoodDriver::parse_frequency(&frame),
+                    Err(_) => None,
+                };
+                let Some(hz) = hz else {
+                    status.push_status(StatusLevel::Error, "ATU blocked: couldn't read the radio's frequency");
+                    return;
+                };
+                if let Err(msg) = check_tune_guard(hz as f64 / 1_000_000.0, min_freq, max_freq) {
+                    status.push_status(StatusLevel::Error, format!("ATU {}", msg));
+                    return;
+                }
+                if let Err(e) = KenwoodDriver::start_atu(&serial).await {
+                    status.push_status(StatusLevel::Error, format!("ATU start failed: {:?}", e));
+                    return;
+                }
+                status.push_status(StatusLevel::Info, "ATU tuning...");
+                for _ in 0..ATU_POLLS {
+                    TimeoutFuture::new(ATU_POLL_MS).await;
+                    let frame = match KenwoodDriver::query_atu(&serial).await {
+                        Ok(frame) => frame,
+                        Err(_) => {
+                            status.push_status(
+                                StatusLevel::Warning,
+                                "No ATU response; the radio may not have an internal tuner",
+                            );
+                            return;
+                        }
+                    };
+                    serial.log_rx(frame.clone());
+                    if KenwoodDriver::atu_tuning(&frame) == Some(false) {
+                        status.push_status(StatusLevel::Success, "ATU tune complete");
+                        return;
+                    }
+                }
+                status.push_status(StatusLevel::Warning, "ATU still tuning; check the radio");
+            });
+        })
+    };
+
+    let on_vfo_a = {
+        let rig = rig.clone();
+        let serial = serial.clone();
+        let status = status.clone();
+        Callback::from(move |_| {
+            let rig = *rig;
+            let serial = serial.clone();
+            let status = status.clone();
+            spawn_local(async move {
+                match rig.driver().set_vfo_a(&serial).await {
+                    Ok(()) => status.push_status(StatusLevel::Info, "VFO A selected"),
+                    Err(e) => status.push_status(StatusLevel::Error, format!("VFO A failed: {:?}", e)),
+                }
+                // try read
+                // response will be streamed to the log by the background reader
+            });
+        })
+    };
+
+    let on_vfo_b = {
+        let rig = rig.clone();
+        let serial = serial.clone();
+        let status = status.clone();
+        Callback::from(move |_| {
+            let rig = *rig;
+            let serial = serial.clone();
+            let status = status.clone();
+            spawn_local(async move {
+                match rig.driver().set_vfo_b(&serial).await {
+                    Ok(()) => status.push_status(StatusLevel::Info, "VFO B selected"),
+                    Err(e) => status.push_status(StatusLevel::Error, format!("VFO B failed: {:?}", e)),
+                }
+                // response will be streamed to the log by the background reader
+            });
+        })
+    };
+
+    let on_set_mode = {
+        let rig = rig.clone();
+        let serial = serial.clone();
+        let status = status.clone();
+        let band_mode_memory = band_mode_memory.clone();
+        let last_tuned_mhz = last_tuned_mhz.clone();
+        Callback::from(move |mode: String| {
+            let rig = *rig;
+            let serial = serial.clone();
+            let status = status.clone();
+            let band_mode_memory = *band_mode_memory;
+            let last_tuned_mhz = last_tuned_mhz.clone();
+            spawn_local(async move {
+                match rig.driver().set_mode(&serial, &mode).await {
+                    Ok(()) => {
+                        if let Some(mhz) = *last_tuned_mhz.borrow() {
+                            if band_mode_memory {
+                                remember_band_mode(mhz, &mode);
+                            }
+                        }
+                        status.push_status(StatusLevel::Info, format!("Mode set: {}", mode));
+                    }
+                    Err(e) => status.push_status(StatusLevel::Error, format!("Set mode failed: {:?}", e)),
+                }
+                // response will be streamed to the log by the background reader
+            });
+        })
+    };
+
+    let on_query_freq = {
+        let rig = rig.clone();
+        let serial = serial.clone();
+        let status = status.clone();
+        let last_rx = last_rx.clone();
+        Callback::from(move |_| {
+            let rig = *rig;
+            let serial = serial.clone();
+            let status = status.clone();
+            let last_rx = last_rx.clone();
+            spawn_local(async move {
+                let driver = rig.driver();
+                match driver.query_frequency(&serial).await {
+                    Ok(resp) => {
+                        match driver.parse_frequency(&resp) {
+                            Some(hz) => status.push_status(StatusLevel::Info, format!("Radio at {} MHz", hz as f64 / 1_000_000.0)),
+                            None => status.push_status(StatusLevel::Info, "Queried frequency"),
+                        }
+                        serial.log_rx(resp.clone());
+                        last_rx.set(resp);
+                    }
+                    Err(e) => status.push_status(StatusLevel::Error, format!("Query failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_ping = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let connected = connected.clone();
+        Callback::from(move |_| {
+            if !*connected {
+                status.push_status(StatusLevel::Error, "Connect serial first");
+                return;
+            }
+            let serial = serial.clone();
+            let status = status.clone();
+            spawn_local(async move {
+                let started = js_sys::Date::now();
+                match KenwoodDriver::ping(&serial).await {
+                    Ok(resp) => {
+                        let elapsed = js_sys::Date::now() - started;
+                        serial.log_rx(resp.clone());
+                        let model = KenwoodDriver::model_name(&resp).unwrap_or("unknown model");
+                        status.push_status(
+                            StatusLevel::Info,
+                            format!("Radio answered {} ({}) in {:.0} ms", resp, model, elapsed),
+                        );
+                    }
+                    Err(_) => status.push_status(StatusLevel::Error, "No answer to ID; check cable, baud rate and CAT settings"),
+                }
+            });
+        })
+    };
+
+    let on_query_smeter = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let last_rx = last_rx.clone();
+        let smeter = smeter.clone();
+        Callback::from(move |_| {
+            let serial = serial.clone();
+            let status = status.clone();
+            let last_rx = last_rx.clone();
+            let smeter = smeter.clone();
+            spawn_local(async move {
+                match KenwoodDriver::query_smeter(&serial).await {
+                    Ok(resp) => {
+                        let entry = match KenwoodDriver::parse_smeter(&resp) {
+                            Some(raw) => {
+                                smeter.set(Some(raw));
+                                status.push_status(StatusLevel::Info, format!("S-meter {}", KenwoodDriver::smeter_label(raw)));
+                                format!("{} ({} = {})", resp, raw, KenwoodDriver::smeter_label(raw))
+                            }
+                            None => {
+                                status.push_status(StatusLevel::Info, "Queried S-meter");
+                                resp.clone()
+                            }
+                        };
+                        serial.log_rx(entry.clone());
+                        last_rx.set(entry);
+                    }
+                    Err(e) => status.push_status(StatusLevel::Error, format!("S-meter query failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_query_split = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let split_state = split_state.clone();
+        Callback::from(move |_| {
+            let serial = serial.clone();
+            let status = status.clone();
+            let split_state = split_state.clone();
+            spawn_local(async move {
+                match KenwoodDriver::query_split(&serial).await {
+                    Ok(state) => {
+                        serial.log_rx(format_split(&state));
+                        split_state.set(Some(state));
+                    }
+                    Err(e) => status.push_status(StatusLevel::Error, format!("Split query failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_rit_step = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let rit_offset = rit_offset.clone();
+        let rit_target = rit_target.clone();
+        Callback::from(move |delta: i32| {
+            let serial = serial.clone();
+            let status = status.clone();
+            let rit_offset = rit_offset.clone();
+            let rit_target = rit_target.clone();
+            let from = *rit_target.borrow();
+            let to = (from + delta).clamp(-RIT_MAX_HZ, RIT_MAX_HZ);
+            *rit_target.borrow_mut() = to;
+            spawn_local(async move {
+                match KenwoodDriver::set_rit(&serial, from, to).await {
+                    Ok(()) => {
+                        rit_offset.set(to);
+                        status.push_status(StatusLevel::Info, format!("RIT {:+} Hz", to));
+                    }
+                    Err(e) => {
+                        // Take back just this step: a click made meanwhile
+                        // has already built on it.
+                        *rit_target.borrow_mut() -= to - from;
+                        status.push_status(StatusLevel::Error, format!("RIT failed: {:?}", e));
+                    }
+                }
+            });
+        })
+    };
+
+    let on_rit_toggle = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let rit_enabled = rit_enabled.clone();
+        Callback::from(move |_: Event| {
+            let serial = serial.clone();
+            let status = status.clone();
+            let rit_enabled = rit_enabled.clone();
+            let enable = !*rit_enabled;
+            spawn_local(async move {
+                match KenwoodDriver::set_rit_enabled(&serial, enable).await {
+                    Ok(()) => rit_enabled.set(enable),
+                    Err(e) => status.push_status(StatusLevel::Error, format!("RIT toggle failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_rit_clear = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let rit_offset = rit_offset.clone();
+        let rit_target = rit_target.clone();
+        Callback::from(move |_| {
+            *rit_target.borrow_mut() = 0;
+            let serial = serial.clone();
+            let status = status.clone();
+            let rit_offset = rit_offset.clone();
+            spawn_local(async move {
+                match KenwoodDriver::clear_rit(&serial).await {
+                    Ok(()) => rit_offset.set(0),
+                    Err(e) => status.push_status(StatusLevel::Error, format!("RIT clear failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_set_power = {
+        let serial = serial.clone();
+        let status = status.clone();
+        Callback::from(move |watts: u32| {
+            let serial = serial.clone();
+            let status = status.clone();
+            spawn_local(async move {
+                match KenwoodDriver::set_power(&serial, watts).await {
+                    Ok(sent) => status.push_status(StatusLevel::Info, format!("Power set: {} W", sent)),
+                    Err(e) => status.push_status(StatusLevel::Error, format!("Set power failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_set_filter_width = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let filter_width = filter_width.clone();
+        Callback::from(move |width: u32| {
+            let serial = serial.clone();
+            let status = status.clone();
+            let filter_width = filter_width.clone();
+            spawn_local(async move {
+                match KenwoodDriver::set_filter_width(&serial, width).await {
+                    Ok(applied) => {
+                        filter_width.set(Some(applied));
+                        status.push_status(StatusLevel::Info, format!("Filter width {} Hz", applied));
+                    }
+                    Err(e) => status.push_status(StatusLevel::Error, format!("Set width failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_query_filter_width = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let filter_width = filter_width.clone();
+        Callback::from(move |_| {
+            let serial = serial.clone();
+            let status = status.clone();
+            let filter_width = filter_width.clone();
+            spawn_local(async move {
+                match KenwoodDriver::query_filter_width(&serial).await {
+                    Ok(width) => filter_width.set(Some(width)),
+                    Err(e) => status.push_status(StatusLevel::Error, format!("Width query failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_noise_blanker = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let noise_blanker = noise_blanker.clone();
+        Callback::from(move |on: bool| {
+            let serial = serial.clone();
+            let status = status.clone();
+            let noise_blanker = noise_blanker.clone();
+            spawn_local(async move {
+                match KenwoodDriver::set_noise_blanker(&serial, on).await {
+                    Ok(()) => noise_blanker.set(Some(on)),
+                    Err(e) => status.push_status(StatusLevel::Error, format!("Noise blanker failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_noise_reduction = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let noise_reduction = noise_reduction.clone();
+        Callback::from(move |level: u8| {
+            let serial = serial.clone();
+            let status = status.clone();
+            let noise_reduction = noise_reduction.clone();
+            spawn_local(async move {
+                match KenwoodDriver::set_noise_reduction(&serial, level).await {
+                    Ok(()) => noise_reduction.set(Some(level)),
+                    Err(e) => status.push_status(StatusLevel::Error, format!("Noise reduction failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_query_noise = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let noise_blanker = noise_blanker.clone();
+        let noise_reduction = noise_reduction.clone();
+        Callback::from(move |_| {
+            let serial = serial.clone();
+            let status = status.clone();
+            let noise_blanker = noise_blanker.clone();
+            let noise_reduction = noise_reduction.clone();
+            spawn_local(async move {
+                match KenwoodDriver::query_noise_blanker(&serial).await {
+                    Ok(on) => noise_blanker.set(Some(on)),
+                    Err(e) => status.push_status(StatusLevel::Error, format!("NB query failed: {:?}", e)),
+                }
+                match KenwoodDriver::query_noise_reduction(&serial).await {
+                    Ok(level) => noise_reduction.set(Some(level)),
+                    Err(e) => status.push_status(StatusLevel::Error, format!("NR query failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_agc = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let agc = agc.clone();
+        Callback::from(move |speed: AgcSpeed| {
+            let serial = serial.clone();
+            let status = status.clone();
+            let agc = agc.clone();
+            spawn_local(async move {
+                match KenwoodDriver::set_agc(&serial, speed).await {
+                    Ok(()) => agc.set(Some(speed)),
+                    Err(e) => status.push_status(StatusLevel::Error, format!("AGC failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_query_agc = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let agc = agc.clone();
+        Callback::from(move |_| {
+            let serial = serial.clone();
+            let status = status.clone();
+            let agc = agc.clone();
+            spawn_local(async move {
+                match KenwoodDriver::query_agc(&serial).await {
+                    Ok(speed) => agc.set(Some(speed)),
+                    Err(e) => status.push_status(StatusLevel::Error, format!("AGC query failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_query_power_state = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let radio_power = radio_power.clone();
+        Callback::from(move |_| {
+            let serial = serial.clone();
+            let status = status.clone();
+            let radio_power = radio_power.clone();
+            spawn_local(async move {
+                match KenwoodDriver::query_power_state(&serial, DEFAULT_FRAME_TIMEOUT_MS).await {
+                    Ok(on) => radio_power.set(Some(on)),
+                    Err(_) => {
+                        radio_power.set(None);
+                        status.push_status(
+                            StatusLevel::Warning,
+                            "No answer to PS; the radio may be in standby with CAT asleep",
+                        );
+                    }
+                }
+            });
+        })
+    };
+
+    let on_power_on = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let radio_power = radio_power.clone();
+        Callback::from(move |_| {
+            let serial = serial.clone();
+            let status = status.clone();
+            let radio_power = radio_power.clone();
+            spawn_local(async move {
+                if let Err(e) = KenwoodDriver::power_on(&serial).await {
+                    status.push_status(StatusLevel::Error, format!("Power on failed: {:?}", e));
+                    return;
+                }
+                status.push_status(StatusLevel::Info, "Sent PS1; waiting for the radio to start...");
+                match KenwoodDriver::query_power_state(&serial, POWER_ON_TIMEOUT_MS).await {
+                    Ok(true) => {
+                        radio_power.set(Some(true));
+                        status.push_status(StatusLevel::Success, "Radio is on");
+                    }
+                    Ok(false) => {
+                        radio_power.set(Some(false));
+                        status.push_status(StatusLevel::Warning, "Radio is still in standby");
+                    }
+                    Err(_) => {
+                        radio_power.set(None);
+                        status.push_status(
+                            StatusLevel::Warning,
+                            "The radio didn't answer after PS1; it may not accept power-on over CAT",
+                        );
+                    }
+                }
+            });
+        })
+    };
+
+    let on_power_off = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let radio_power = radio_power.clone();
+        Callback::from(move |_| {
+            let confirmed = web_sys::window()
+                .and_then(|w| w.confirm_with_message("Put the radio in standby?").ok())
+                .unwrap_or(false);
+            if !confirmed {
+                return;
+            }
+            let serial = serial.clone();
+            let status = status.clone();
+            let radio_power = radio_power.clone();
+            spawn_local(async move {
+                match KenwoodDriver::power_off(&serial).await {
+                    Ok(()) => {
+                        radio_power.set(Some(false));
+                        status.push_status(StatusLevel::Info, "Radio put in standby");
+                    }
+                    Err(e) => status.push_status(StatusLevel::Error, format!("Power off failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_keyer_speed_change = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let keyer_wpm = keyer_wpm.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let Ok(value) = input.value().trim().parse::<u32>() else {
+                status.push_status(StatusLevel::Error, "Keyer speed must be a whole number of WPM");
+                return;
+            };
+            let serial = serial.clone();
+            let status = status.clone();
+            let keyer_wpm = keyer_wpm.clone();
+            spawn_local(async move {
+                match KenwoodDriver::set_keyer_speed(&serial, value).await {
+                    Ok(sent) => {
+                        keyer_wpm.set(sent);
+                        status.push_status(StatusLevel::Info, format!("Keyer speed {} WPM", sent));
+                    }
+                    Err(e) => status.push_status(StatusLevel::Error, format!("Set keyer speed failed: {:?}", e)),
+                }
+            });
         })
     };
 
-    let on_send_raw = {
-        let raw_cmd = raw_cmd.clone();
+    let on_query_keyer_speed = {
         let serial = serial.clone();
         let status = status.clone();
+        let last_rx = last_rx.clone();
+        let keyer_wpm = keyer_wpm.clone();
         Callback::from(move |_| {
-            let cmd = (*raw_cmd).clone();
             let serial = serial.clone();
             let status = status.clone();
+            let last_rx = last_rx.clone();
+            let keyer_wpm = keyer_wpm.clone();
             spawn_local(async move {
-                if cmd.is_empty() {
-                    status.set("Empty raw command".to_string());
-                    return;
+                match KenwoodDriver::query_keyer_speed(&serial).await {
+                    Ok(resp) => {
+                        let entry = match KenwoodDriver::parse_keyer_speed(&resp) {
+                            Some(wpm) => {
+                                keyer_wpm.set(wpm);
+                                format!("{} ({} WPM)", resp, wpm)
+                            }
+                            None => resp.clone(),
+                        };
+                        serial.log_rx(entry.clone());
+                        last_rx.set(entry);
+                    }
+                    Err(e) => status.push_status(StatusLevel::Error, format!("Keyer speed query failed: {:?}", e)),
+                }
+            });
+        })
+    };
+
+    let on_query_power = {
+        let serial = serial.clone();
+        let status = status.clone();
+        let last_rx = last_rx.clone();
+        Callback::from(move |_| {
+            let serial = serial.clone();
+            let status = status.clone();
+            let last_rx = last_rx.clone();
+            spawn_local(async move {
+                match KenwoodDriver::query_power(&serial).await {
+                    Ok(resp) => {
+                        let entry = match KenwoodDriver::parse_power(&resp) {
+                            Some(watts) => {
+                                status.push_status(StatusLevel::Info, format!("Power {} W", watts));
+                                format!("{} ({} W)", resp, watts)
+                            }
+                            None => {
+                                status.push_status(StatusLevel::Info, "Queried power");
+                                resp.clone()
+                            }
+                        };
+                        serial.log_rx(entry.clone());
+                        last_rx.set(entry);
+                    }
+                    Err(e) => status.push_status(StatusLevel::Error, format!("Power query failed: {:?}", e)),
                 }
-                match KenwoodDriver::send_raw(&*serial, &cmd).await {
-                    Ok(()) => status.set("Raw command sent".to_string()),
-                    Err(e) => status.set(format!("Send failed: {:?}", e)),
+            });
+        })
+    };
+
+    let on_log_tx_toggle = {
+        let serial = serial.clone();
+        let log_tx = log_tx.clone();
+        Callback::from(move |_: Event| {
+            let value = !*log_tx;
+            settings::set(STORAGE_LOG_TX, value);
+            serial.set_log_tx(value);
+            log_tx.set(value);
+        })
+    };
+
+    let on_clear_log = {
+        let serial = serial.clone();
+        let last_rx = last_rx.clone();
+        Callback::from(move |_| {
+            serial.clear_log();
+            last_rx.set(String::new());
+        })
+    };
+
+    let on_copy_spot = {
+        let status = status.clone();
+        Callback::from(move |text: String| {
+            let status = status.clone();
+            spawn_local(async move {
+                match copy_text(&text).await {
+                    Ok(()) => status.push_status(StatusLevel::Success, format!("Copied {}", text)),
+                    Err(err) => status.push_status(StatusLevel::Error, format!("Copy failed: {:?}", err)),
                 }
             });
         })
     };
 
-    let on_raw_input = {
-        let raw_cmd = raw_cmd.clone();
-        Callback::from(move |e: InputEvent| {
-            let input: HtmlInputElement = e.target_unchecked_into();
-            raw_cmd.set(input.value());
+    let on_copy_details_toggle = {
+        let copy_details = copy_details.clone();
+        Callback::from(move |_: Event| {
+            let value = !*copy_details;
+            settings::set(STORAGE_COPY_DETAILS, value);
+            copy_details.set(value);
         })
     };
 
-    let on_test_14062 = {
+    let on_copy_log = {
         let serial = serial.clone();
         let status = status.clone();
         Callback::from(move |_| {
-            let serial = serial.clone();
+            let text = serial
+                .log_entries()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n");
             let status = status.clone();
             spawn_local(async move {
-                match KenwoodDriver::test_tune(&*serial).await {
-                    Ok(()) => status.set("14.062 CW test tune sent".to_string()),
-                    Err(e) => status.set(format!("Test tune failed: {:?}", e)),
+                match copy_text(&text).await {
+                    Ok(()) => status.push_status(StatusLevel::Success, "Copied"),
+                    Err(err) => status.push_status(StatusLevel::Error, format!("Copy failed: {:?}", err)),
                 }
             });
         })
     };
 
-    let on_vfo_a = {
-        let serial = serial.clone();
-        let status = status.clone();
-        Callback::from(move |_| {
-            let serial = serial.clone();
-            let status = status.clone();
-            spawn_local(async move {
-                match KenwoodDriver::set_vfo_a(&*serial).await {
-                    Ok(()) => status.set("VFO A selected".to_string()),
-                    Err(e) => status.set(format!("VFO A failed: {:?}", e)),
-                }
-                // try read
-                // response will be streamed to the log by the background reader
-            });
+    // explicit on-demand read removed; background stream supplies responses
+
+    let connect_class = if *connected { "connected" } else { "" };
+
+    let pending_min_save = use_mut_ref(|| None::<Timeout>);
+    let pending_max_save = use_mut_ref(|| None::<Timeout>);
+
+    // An edit that would invert the range is held back rather than applied:
+    // half-typed values are often briefly inverted (typing 10.150 passes
+    // through 1), so the error waits until the edit is committed.
+    let on_min_change = {
+        let min_freq = min_freq.clone();
+        let status = status.clone();
+        let pending = pending_min_save.clone();
+        let max_freq = *max_freq;
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            match parse_decimal(&input.value()) {
+                Some(value) if value < max_freq => {
+                    min_freq.set(value);
+                    debounce_save(&pending, STORAGE_MIN_FREQ, value);
+                }
+                Some(_) => {}
+                None => status.push_status(StatusLevel::Error, "Invalid min frequency".to_string()),
+            }
+        })
+    };
+
+    let on_max_change = {
+        let max_freq = max_freq.clone();
+        let status = status.clone();
+        let pending = pending_max_save.clone();
+        let min_freq = *min_freq;
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            match parse_decimal(&input.value()) {
+                Some(value) if value > min_freq => {
+                    max_freq.set(value);
+                    debounce_save(&pending, STORAGE_MAX_FREQ, value);
+                }
+                Some(_) => {}
+                None => status.push_status(StatusLevel::Error, "Invalid max frequency".to_string()),
+            }
+        })
+    };
+
+    // On commit, a number that differs from `current` is one the input
+    // handler held back as inverted: report it and put the last valid value
+    // back in the field.
+    let on_range_commit = |current: f64| {
+        let status = status.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if parse_decimal(&input.value()).is_some_and(|value| value != current) {
+                status.push_status(StatusLevel::Error, RANGE_INVERTED);
+                input.set_value(&format!("{:.3}", current));
+            }
+        })
+    };
+    let on_min_commit = on_range_commit(*min_freq);
+    let on_max_commit = on_range_commit(*max_freq);
+
+    let on_band_change = {
+        let band_filter = band_filter.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let value = select.value();
+            settings::set(STORAGE_BAND, &value);
+            band_filter.set(if value.is_empty() { None } else { Some(value) });
+        })
+    };
+
+    let on_split_toggle = {
+        let split_enabled = split_enabled.clone();
+        Callback::from(move |_: Event| {
+            let value = !*split_enabled;
+            settings::set(STORAGE_SPLIT, value);
+            split_enabled.set(value);
+        })
+    };
+
+    let on_spot_count_change = {
+        let spot_count = spot_count.clone();
+        let spots_api = spots_api.clone();
+        let sources = sources.clone();
+        let spots_epoch = spots_epoch.clone();
+        let status = status.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            match input.value().trim().parse::<u32>() {
+                Ok(count) if count > 0 => {
+                    *spots_epoch.borrow_mut() = None;
+                    spot_count.set(count);
+                    settings::set(STORAGE_SPOT_COUNT, count);
+                    *sources.borrow_mut() = build_sources(&spots_api, count);
+                }
+                _ => status.push_status(StatusLevel::Error, "Spot count must be a positive whole number".to_string()),
+            }
+        })
+    };
+
+    let on_spots_api_change = {
+        let spot_count = spot_count.clone();
+        let spots_api = spots_api.clone();
+        let sources = sources.clone();
+        let spots_epoch = spots_epoch.clone();
+        let status = status.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let value = input.value().trim().to_string();
+            if value.starts_with("https://") || value.starts_with("http://") {
+                settings::set(STORAGE_SPOTS_API, &value);
+                *sources.borrow_mut() = build_sources(&value, *spot_count);
+                *spots_epoch.borrow_mut() = None;
+                spots_api.set(value);
+            } else {
+                status.push_status(StatusLevel::Error, "Spots API must be an http(s) URL".to_string());
+            }
+        })
+    };
+
+    let on_rig_change = {
+        let rig = rig.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(value) = Rig::from_key(&select.value()) {
+                settings::set(STORAGE_RIG, value.key());
+                rig.set(value);
+            }
+        })
+    };
+
+    let on_kenwood_model_change = {
+        let kenwood_model = kenwood_model.clone();
+        let serial = serial.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(model) = KenwoodModel::from_key(&select.value()) {
+                settings::set(STORAGE_KENWOOD_MODEL, model.key());
+                serial.set_kenwood_model(model);
+                kenwood_model.set(model);
+            }
+        })
+    };
+
+    let on_baud_change = {
+        let baud_rate = baud_rate.clone();
+        let connected = connected.clone();
+        let status = status.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Ok(value) = select.value().parse::<u32>() {
+                baud_rate.set(value);
+                settings::set(STORAGE_BAUD_RATE, value);
+                if *connected {
+                    status.push_status(StatusLevel::Info, format!("Baud set to {}; reconnect to apply", value));
+                }
+            }
+        })
+    };
+
+    let on_watchlist_input = {
+        let watchlist = watchlist.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let value = input.value();
+            settings::set(STORAGE_WATCHLIST, &value);
+            watchlist.set(value);
+        })
+    };
+
+    let on_my_summit_input = {
+        let my_summit = my_summit.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let value = input.value().trim().to_uppercase();
+            settings::set(STORAGE_MY_SUMMIT, &value);
+            my_summit.set(value);
+        })
+    };
+
+    let on_my_grid_input = {
+        let my_grid = my_grid.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let value = input.value().trim().to_string();
+            settings::set(STORAGE_MY_GRID, &value);
+            my_grid.set(value);
+        })
+    };
+
+    let on_s2s_only_toggle = {
+        let s2s_only = s2s_only.clone();
+        Callback::from(move |_: Event| {
+            let value = !*s2s_only;
+            settings::set(STORAGE_S2S_ONLY, value);
+            s2s_only.set(value);
+        })
+    };
+
+    let on_watch_beep_toggle = {
+        let watch_beep = watch_beep.clone();
+        Callback::from(move |_: Event| {
+            let value = !*watch_beep;
+            settings::set(STORAGE_WATCH_BEEP, value);
+            watch_beep.set(value);
+        })
+    };
+
+    let on_alert_mute_toggle = {
+        let alert_muted = alert_muted.clone();
+        Callback::from(move |_: Event| {
+            let value = !*alert_muted;
+            settings::set(STORAGE_ALERT_MUTED, value);
+            alert_muted.set(value);
+        })
+    };
+
+    let on_tune_filter_toggle = {
+        let tune_filter_enabled = tune_filter_enabled.clone();
+        Callback::from(move |_: Event| {
+            let value = !*tune_filter_enabled;
+            settings::set(STORAGE_TUNE_FILTER, value);
+            tune_filter_enabled.set(value);
+        })
+    };
+
+    let on_tune_width_change = |state: &UseStateHandle<u32>, key: &'static str| {
+        let state = state.clone();
+        let status = status.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            match input.value().trim().parse::<u32>() {
+                Ok(hz) if hz > 0 => {
+                    settings::set(key, hz as f64);
+                    state.set(hz);
+                }
+                _ => status.push_status(StatusLevel::Error, "Width must be a positive number of Hz"),
+            }
+        })
+    };
+    let on_cw_width_change = on_tune_width_change(&cw_width, STORAGE_CW_WIDTH);
+    let on_ssb_width_change = on_tune_width_change(&ssb_width, STORAGE_SSB_WIDTH);
+
+    let on_band_mode_memory_toggle = {
+        let band_mode_memory = band_mode_memory.clone();
+        Callback::from(move |_: Event| {
+            let value = !*band_mode_memory;
+            settings::set(STORAGE_BAND_MODE_MEMORY, value);
+            band_mode_memory.set(value);
+        })
+    };
+
+    let on_digital_dial_toggle = {
+        let digital_dial_enabled = digital_dial_enabled.clone();
+        Callback::from(move |_: Event| {
+            let value = !*digital_dial_enabled;
+            settings::set(STORAGE_DIGITAL_DIAL, value);
+            digital_dial_enabled.set(value);
+        })
+    };
+
+    let on_verify_toggle = {
+        let verify_tune_enabled = verify_tune_enabled.clone();
+        Callback::from(move |_: Event| {
+            let value = !*verify_tune_enabled;
+            settings::set(STORAGE_VERIFY_TUNE, value);
+            verify_tune_enabled.set(value);
+        })
+    };
+
+    let on_stale_change = {
+        let stale_minutes = stale_minutes.clone();
+        let status = status.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            match input.value().parse::<f64>() {
+                Ok(value) if value > 0.0 => {
+                    stale_minutes.set(value);
+                    settings::set(STORAGE_STALE_MINUTES, value);
+                }
+                _ => status.push_status(StatusLevel::Error, "Invalid age threshold".to_string()),
+            }
+        })
+    };
+
+    let on_refresh_secs_change = {
+        let refresh_secs = refresh_secs.clone();
+        let status = status.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            match input.value().parse::<f64>() {
+                Ok(value) if value.is_finite() => {
+                    let secs = (value.round() as u32).clamp(MIN_REFRESH_SECS, MAX_REFRESH_SECS);
+                    settings::set(STORAGE_REFRESH_SECS, secs as f64);
+                    refresh_secs.set(secs);
+                }
+                _ => status.push_status(StatusLevel::Error, "Invalid refresh interval".to_string()),
+            }
+        })
+    };
+
+    let on_command_delay_change = {
+        let command_delay = command_delay.clone();
+        let serial = serial.clone();
+        let status = status.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            match input.value().parse::<f64>() {
+                Ok(value) if value.is_finite() => {
+                    let ms = (value.round() as u32).clamp(MIN_COMMAND_DELAY_MS, MAX_COMMAND_DELAY_MS);
+                    settings::set(STORAGE_COMMAND_DELAY, ms as f64);
+                    serial.set_command_delay(ms);
+                    command_delay.set(ms);
+                }
+                _ => status.push_status(StatusLevel::Error, "Invalid command delay".to_string()),
+            }
+        })
+    };
+
+    let on_clash_change = {
+        let clash_khz = clash_khz.clone();
+        let status = status.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            match parse_decimal(&input.value()) {
+                Some(value) if value >= 0.0 => {
+                    clash_khz.set(value);
+                    settings::set(STORAGE_CLASH_KHZ, value);
+                }
+                _ => status.push_status(StatusLevel::Error, "Invalid clash threshold".to_string()),
+            }
+        })
+    };
+
+    let on_dedup_change = {
+        let dedup_minutes = dedup_minutes.clone();
+        let status = status.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            match input.value().parse::<f64>() {
+                Ok(value) if value >= 0.0 => {
+                    *dedup_minutes.borrow_mut() = value;
+                    settings::set(STORAGE_DEDUP_MINUTES, value);
+                }
+                _ => status.push_status(StatusLevel::Error, "Invalid merge window".to_string()),
+            }
+        })
+    };
+
+    let on_mode_toggle = {
+        let mode_filter = mode_filter.clone();
+        Callback::from(move |group: &'static str| {
+            let mut selected = (*mode_filter).clone();
+            if let Some(pos) = selected.iter().position(|m| m == group) {
+                selected.remove(pos);
+            } else {
+                selected.push(group.to_string());
+            }
+            settings::set(STORAGE_MODES, selected.join(","));
+            mode_filter.set(selected);
+        })
+    };
+
+    let on_region_toggle = {
+        let region_filter = region_filter.clone();
+        Callback::from(move |region: &'static str| {
+            let mut selected = (*region_filter).clone();
+            if let Some(pos) = selected.iter().position(|r| r == region) {
+                selected.remove(pos);
+            } else {
+                selected.push(region.to_string());
+            }
+            settings::set(STORAGE_REGIONS, selected.join(","));
+            region_filter.set(selected);
+        })
+    };
+
+    let on_association_input = {
+        let association_filter = association_filter.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let value = input.value();
+            settings::set(STORAGE_ASSOCIATIONS, &value);
+            association_filter.set(value);
+        })
+    };
+
+    let on_comment_filter_input = {
+        let comment_filter = comment_filter.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let value = input.value();
+            settings::set(STORAGE_COMMENT_FILTER, &value);
+            comment_filter.set(value);
+        })
+    };
+
+    let on_favorite_toggle = {
+        let favorites = favorites.clone();
+        Callback::from(move |summit: String| {
+            let mut starred = (*favorites).clone();
+            if let Some(pos) = starred.iter().position(|f| *f == summit) {
+                starred.remove(pos);
+            } else {
+                starred.push(summit);
+            }
+            save_favorites(&starred);
+            favorites.set(starred);
+        })
+    };
+
+    let on_worked_toggle = {
+        let worked = worked.clone();
+        Callback::from(move |key: String| {
+            let mut done = (*worked).clone();
+            if let Some(pos) = done.iter().position(|w| *w == key) {
+                done.remove(pos);
+            } else {
+                done.push(key);
+            }
+            save_worked(&done);
+            worked.set(done);
+        })
+    };
+
+    // Drop the list and its cache and fetch it again in full, e.g. after
+    // the cache has gone stale.
+    let on_clear_spots = {
+        let fetch_ctx = fetch_ctx.clone();
+        let selected_row = selected_row.clone();
+        let highlighted = highlighted.clone();
+        Callback::from(move |_| {
+            settings::remove(STORAGE_SPOTS_CACHE);
+            *fetch_ctx.epoch.borrow_mut() = None;
+            fetch_ctx.cached_at.set(None);
+            fetch_ctx.loaded.set(false);
+            fetch_ctx.spots.set(Vec::new());
+            selected_row.set(None);
+            highlighted.set(None);
+            fetch_spots(fetch_ctx.clone());
+        })
+    };
+
+    let on_clear_worked = {
+        let worked = worked.clone();
+        let status = status.clone();
+        Callback::from(move |_| {
+            save_worked(&[]);
+            worked.set(Vec::new());
+            status.push_status(StatusLevel::Info, "Cleared worked spots");
+        })
+    };
+
+    let on_show_map_toggle = {
+        let show_map = show_map.clone();
+        Callback::from(move |_: Event| {
+            let value = !*show_map;
+            settings::set(STORAGE_SHOW_MAP, value);
+            show_map.set(value);
+        })
+    };
+
+    let on_map_tunes_toggle = {
+        let map_tunes = map_tunes.clone();
+        Callback::from(move |_: Event| {
+            let value = !*map_tunes;
+            settings::set(STORAGE_MAP_TUNES, value);
+            map_tunes.set(value);
+        })
+    };
+
+    let on_poll_tx_toggle = {
+        let poll_tx = poll_tx.clone();
+        Callback::from(move |_: Event| {
+            let value = !*poll_tx;
+            settings::set(STORAGE_POLL_TX, value);
+            poll_tx.set(value);
         })
     };
 
-    let on_vfo_b = {
-        let serial = serial.clone();
-        let status = status.clone();
-        Callback::from(move |_| {
-            let serial = serial.clone();
-            let status = status.clone();
-            spawn_local(async move {
-                match KenwoodDriver::set_vfo_b(&*serial).await {
-                    Ok(()) => status.set("VFO B selected".to_string()),
-                    Err(e) => status.set(format!("VFO B failed: {:?}", e)),
-                }
-                // response will be streamed to the log by the background reader
-            });
+    let on_compact_toggle = {
+        let compact = compact.clone();
+        Callback::from(move |_: Event| {
+            let value = !*compact;
+            settings::set(STORAGE_COMPACT, value);
+            compact.set(value);
         })
     };
 
-    let on_set_mode = {
-        let serial = serial.clone();
-        let status = status.clone();
-        Callback::from(move |mode: String| {
-            let serial = serial.clone();
-            let status = status.clone();
-            spawn_local(async move {
-                match KenwoodDriver::set_mode(&*serial, &mode).await {
-                    Ok(()) => status.set(format!("Mode set: {}", mode)),
-                    Err(e) => status.set(format!("Set mode failed: {:?}", e)),
-                }
-                // response will be streamed to the log by the background reader
-            });
+    let on_compact_hide_comments_toggle = {
+        let compact_hide_comments = compact_hide_comments.clone();
+        Callback::from(move |_: Event| {
+            let value = !*compact_hide_comments;
+            settings::set(STORAGE_COMPACT_HIDE_COMMENTS, value);
+            compact_hide_comments.set(value);
         })
     };
 
-    let on_query_freq = {
-        let serial = serial.clone();
-        let status = status.clone();
-        let response_log = response_log.clone();
-        let last_rx = last_rx.clone();
-        Callback::from(move |_| {
-            let serial = serial.clone();
-            let status = status.clone();
-            let response_log = response_log.clone();
-            let last_rx = last_rx.clone();
-            spawn_local(async move {
-                match KenwoodDriver::query_frequency(&*serial).await {
-                    Ok(resp) => {
-                        status.set("Queried frequency".to_string());
-                        let mut v = (*response_log).clone();
-                        let entry = format!("RX: {}", resp);
-                        web_sys::console::log_1(&JsValue::from_str(&format!("app: push query resp {}", entry)));
-                        v.push(entry.clone());
-                        response_log.set(v);
-                        last_rx.set(entry);
-                    }
-                    Err(e) => status.set(format!("Query failed: {:?}", e)),
-                }
-            });
+    let on_favorites_only_toggle = {
+        let favorites_only = favorites_only.clone();
+        Callback::from(move |_: Event| {
+            let value = !*favorites_only;
+            settings::set(STORAGE_FAVORITES_ONLY, value);
+            favorites_only.set(value);
         })
     };
 
-    // explicit on-demand read removed; background stream supplies responses
+    let now_ms = js_sys::Date::now();
 
-    let connect_class = if *connected { "connected" } else { "" };
+    // Display filters only hide rows; indices still refer into `spots` so the
+    // tune callback picks the right entry. Favorite summits skip the filters
+    // and float to the top.
+    let is_favorite = |spot: &Spot| favorites.contains(&spot.summit);
+    let s2s_active = !my_summit.is_empty();
+    let my_qth = grid_to_latlon(&my_grid);
+    let mut visible_spots = spots
+        .iter()
+        .enumerate()
+        .filter(|(_, spot)| {
+            if *s2s_only && s2s_active && !is_s2s_candidate(spot, &my_summit) {
+                return false;
+            }
+            if *favorites_only {
+                return is_favorite(spot);
+            }
+            is_favorite(spot)
+                || SpotFilters {
+                    band: band_filter.as_deref(),
+                    modes: &mode_filter,
+                    regions: &region_filter,
+                    associations: &association_filter,
+                    comments: &comment_filter,
+                }
+                .matches(spot)
+        })
+        .collect::<Vec<_>>();
+    if let Some((column, ascending)) = *sort {
+        visible_spots.sort_by(|(_, a), (_, b)| {
+            let ordering = column.compare(a, b);
+            let ordering = if ascending { ordering } else { ordering.reverse() };
+            column.is_missing(a).cmp(&column.is_missing(b)).then(ordering)
+        });
+    }
+    visible_spots.sort_by_key(|(_, spot)| !is_favorite(spot));
 
-    let on_min_change = {
-        let min_freq = min_freq.clone();
-        let status = status.clone();
-        Callback::from(move |e: InputEvent| {
-            let input: HtmlInputElement = e.target_unchecked_into();
-            if let Ok(value) = input.value().parse::<f64>() {
-                min_freq.set(value);
-                save_freq(STORAGE_MIN_FREQ, value);
-            } else {
-                status.set("Invalid min frequency".to_string());
+    let (page_start, page_end) = page_bounds(visible_spots.len(), *page, PAGE_SIZE);
+    let page_count = visible_spots.len().div_ceil(PAGE_SIZE).max(1);
+    let current_page = page_start / PAGE_SIZE;
+
+    *key_handler.borrow_mut() = {
+        let order = visible_spots.iter().map(|&(idx, _)| idx).collect::<Vec<_>>();
+        let highlighted = highlighted.clone();
+        let page = page.clone();
+        let on_tune = on_tune.clone();
+        Callback::from(move |event: KeyboardEvent| {
+            if is_typing_target(&event) || order.is_empty() {
+                return;
+            }
+            let position = highlighted.and_then(|idx| order.iter().position(|&i| i == idx));
+            match event.key().as_str() {
+                "ArrowDown" => {
+                    event.prevent_default();
+                    let next = position.map_or(0, |p| (p + 1).min(order.len() - 1));
+                    page.set(next / PAGE_SIZE);
+                    highlighted.set(Some(order[next]));
+                }
+                "ArrowUp" => {
+                    event.prevent_default();
+                    let next = position.map_or(0, |p| p.saturating_sub(1));
+                    page.set(next / PAGE_SIZE);
+                    highlighted.set(Some(order[next]));
+                }
+                "Enter" => {
+                    if let Some(p) = position {
+                        event.prevent_default();
+                        on_tune.emit(order[p]);
+                    }
+                }
+                _ => {}
             }
         })
     };
 
-    let on_max_change = {
-        let max_freq = max_freq.clone();
+    let sort_header = |column: SortColumn, label: &'static str, class: &'static str| {
+        let onclick = {
+            let sort = sort.clone();
+            Callback::from(move |_| {
+                let ascending = match *sort {
+                    Some((current, ascending)) if current == column => !ascending,
+                    _ => true,
+                };
+                sort.set(Some((column, ascending)));
+            })
+        };
+        let (arrow, aria_sort) = match *sort {
+            Some((current, true)) if current == column => (" ▲", "ascending"),
+            Some((current, false)) if current == column => (" ▼", "descending"),
+            _ => ("", "none"),
+        };
+        html! {
+            <th class={classes!("sortable", class)} aria-sort={aria_sort} {onclick}>{ label }{ arrow }</th>
+        }
+    };
+
+    let shown = |column: Column| !hidden_columns.contains(&column);
+    let show_comments = shown(Column::Comments) && !(*compact && *compact_hide_comments);
+    let show_bearing = shown(Column::Bearing) && my_qth.is_some();
+    // Star and worked buttons, plus every data column on show.
+    let column_count = 2 + Column::ALL
+        .into_iter()
+        .filter(|&column| match column {
+            Column::Comments => show_comments,
+            Column::Bearing => show_bearing,
+            column => shown(column),
+        })
+        .count();
+
+    let on_column_toggle = |column: Column| {
+        let hidden_columns = hidden_columns.clone();
         let status = status.clone();
-        Callback::from(move |e: InputEvent| {
-            let input: HtmlInputElement = e.target_unchecked_into();
-            if let Ok(value) = input.value().parse::<f64>() {
-                max_freq.set(value);
-                save_freq(STORAGE_MAX_FREQ, value);
-            } else {
-                status.set("Invalid max frequency".to_string());
+        Callback::from(move |e: Event| match toggle_hidden_column(&hidden_columns, column) {
+            Some(next) => {
+                save_hidden_columns(&next);
+                hidden_columns.set(next);
+            }
+            None => {
+                status.push_status(StatusLevel::Warning, "Callsign and Frequency can't both be hidden");
+                let input: HtmlInputElement = e.target_unchecked_into();
+                input.set_checked(true);
             }
         })
     };
 
+    let on_dismiss_storage_warning = {
+        let storage_warning = storage_warning.clone();
+        Callback::from(move |_| storage_warning.set(false))
+    };
+
+    // The radio panel's Kenwood-only controls call `KenwoodDriver`
+    // directly, so they're only offered with a Kenwood selected.
+    let kenwood = *rig == Rig::Kenwood;
+
     html! {
         <div class="app">
+            { if *storage_warning {
+                html! {
+                    <div class="storage-banner" role="alert">
+                        <span>{"Browser storage is unavailable (private browsing or blocked cookies?), so settings won't be saved between visits."}</span>
+                        <button onclick={on_dismiss_storage_warning}>{"Dismiss"}</button>
+                    </div>
+                }
+            } else { html! {} } }
             <div class="header">
-                <button class="settings" onclick={on_toggle_settings}>{"⚙"}</button>
-                <button class={connect_class} onclick={on_connect} disabled={*connected}>{
+                <span class="settings-anchor">
+                    <button
+                        class="settings"
+                        onclick={on_toggle_settings}
+                        title="Settings"
+                        aria-label="Settings"
+                        aria-expanded={show_settings.to_string()}
+                    >{"⚙"}</button>
+                    { if *show_onboarding {
+                        html! {
+                            <div class="onboarding-tip" role="note">
+                                <span>{"New here? Click Connect Serial to pick your radio's port, then open ⚙ for radio controls. Click any spot to tune to it."}</span>
+                                <button onclick={on_dismiss_onboarding.reform(|_: MouseEvent| ())}>{"Got it"}</button>
+                            </div>
+                        }
+                    } else { html! {} } }
+                </span>
+                <button
+                    class={classes!("toggle", "tuning-lock", tuning_locked.then_some("active"))}
+                    onclick={on_toggle_tuning_lock}
+                    title="When locked, clicking a spot never retunes the radio"
+                >{ if *tuning_locked { "🔒 Tuning locked" } else { "🔓 Tuning unlocked" } }</button>
+                <button
+                    class={classes!("toggle", "tx-enable", tx_enabled.then_some("active"))}
+                    onclick={on_toggle_tx}
+                    title="Allow commands that can key the transmitter (TX, ATU, split, test tune)"
+                >{ if *tx_enabled { "TX enabled" } else { "TX disabled" } }</button>
+                <button
+                    class={classes!("toggle", "auto-tune", (*auto_tune && *tx_enabled).then_some("armed"), auto_tune.then_some("active"))}
+                    onclick={on_toggle_auto_tune}
+                    title="Tune each new spot that matches the filters, once. Needs TX enabled."
+                >{ match (*auto_tune, *tx_enabled) {
+                    (true, true) => "⚡ Auto-tune ARMED",
+                    (true, false) => "Auto-tune (needs TX)",
+                    (false, _) => "Auto-tune off",
+                } }</button>
+                { if *on_air {
+                    html! { <span class="on-air" role="status">{"ON AIR"}</span> }
+                } else { html! {} } }
+                <button
+                    class={connect_class}
+                    onclick={on_connect}
+                    disabled={*connected}
+                    aria-label={if *connected { "Connected to the radio" } else { "Connect to the radio's serial port" }}
+                >{
                     if *connected { "Connected" } else { "Connect Serial" }
                 }</button>
+                { if *connected {
+                    html! {
+                        <>
+                            { match serial.port_label() {
+                                Some(label) => html! { <span class="port-label">{ format!("Connected: {}", label) }</span> },
+                                None => html! {},
+                            } }
+                            { match *radio_mode {
+                                Some(mode) => html! { <span class="radio-mode" title="Radio's mode at connect">{ mode }</span> },
+                                None => html! {},
+                            } }
+                            <button onclick={on_disconnect}>{"Disconnect"}</button>
+                        </>
+                    }
+                } else { html! {} } }
                 <button onclick={on_refresh}>{"Refresh"}</button>
+                <button
+                    class={classes!("toggle", show_self_spot.then_some("active"))}
+                    onclick={on_toggle_self_spot}
+                    title="Post a spot for your own activation"
+                >{"Self-spot"}</button>
+                <button onclick={on_clear_spots} title="Empty the table and fetch the spot list again">{"Clear spots"}</button>
+                <button onclick={on_clear_worked} title="Reset worked checkmarks for a new day">{"Clear worked"}</button>
+                <button onclick={on_export_adif}>{ format!("Export ADIF ({})", tuned_contacts.borrow().len()) }</button>
+                <span class="countdown" title="Time until the next automatic refresh">
+                    { format!("{}:{:02}", *countdown_secs / 60, *countdown_secs % 60) }
+                </span>
+                { if let Some(saved_ms) = *cached_at {
+                    html! {
+                        <span class="cached" title="Showing the last saved spot list until fresh data arrives">
+                            { format!("cached {}", format_clock(saved_ms)) }
+                        </span>
+                    }
+                } else { html! {} } }
                 <label>
                     {"Allow tuning from"}
                     <input
-                        type="number"
-                        step="0.001"
+                        type="text"
+                        inputmode="decimal"
+                        class="freq-input"
                         value={format!("{:.3}", *min_freq)}
                         oninput={on_min_change}
+                        onchange={on_min_commit}
                     />
                 </label>
                 <label>
                     {" to "}
                     <input
-                        type="number"
-                        step="0.001"
+                        type="text"
+                        inputmode="decimal"
+                        class="freq-input"
                         value={format!("{:.3}", *max_freq)}
                         oninput={on_max_change}
+                        onchange={on_max_commit}
+                    />
+                </label>
+                <select class="band" onchange={on_band_change}>
+                    <option value="" selected={band_filter.is_none()}>{"All bands"}</option>
+                    { for BANDS.iter().map(|(label, _, _)| html! {
+                        <option value={*label} selected={band_filter.as_deref() == Some(*label)}>{ *label }</option>
+                    }) }
+                </select>
+                <div class="mode-filters">
+                    { for MODE_GROUPS.iter().map(|&group| {
+                        let onchange = {
+                            let cb = on_mode_toggle.clone();
+                            Callback::from(move |_: Event| cb.emit(group))
+                        };
+                        html! {
+                            <label>
+                                <input
+                                    type="checkbox"
+                                    checked={mode_filter.iter().any(|m| m == group)}
+                                    {onchange}
+                                />
+                                { group }
+                            </label>
+                        }
+                    }) }
+                </div>
+                <div class="mode-filters region-filters">
+                    { for REGIONS.iter().map(|&(region, _)| {
+                        let onchange = {
+                            let cb = on_region_toggle.clone();
+                            Callback::from(move |_: Event| cb.emit(region))
+                        };
+                        html! {
+                            <label>
+                                <input
+                                    type="checkbox"
+                                    checked={region_filter.iter().any(|r| r == region)}
+                                    {onchange}
+                                />
+                                { region }
+                            </label>
+                        }
+                    }) }
+                    <label>
+                        <input type="checkbox" checked={*favorites_only} onchange={on_favorites_only_toggle} />
+                        {"★ only"}
+                    </label>
+                    { if s2s_active {
+                        html! {
+                            <label title={format!("Spots mentioning S2S or {}", *my_summit)}>
+                                <input type="checkbox" checked={*s2s_only} onchange={on_s2s_only_toggle} />
+                                {"S2S only"}
+                            </label>
+                        }
+                    } else { html! {} } }
+                    <input
+                        type="text"
+                        class="association-filter"
+                        placeholder="Associations, e.g. W6, W7A"
+                        value={(*association_filter).clone()}
+                        oninput={on_association_input}
                     />
+                    <input
+                        type="search"
+                        class="comment-filter"
+                        placeholder="Comments contain, e.g. QRP"
+                        value={(*comment_filter).clone()}
+                        oninput={on_comment_filter_input}
+                    />
+                </div>
+                <label title="Tighter rows for small screens">
+                    <input type="checkbox" checked={*compact} onchange={on_compact_toggle} />
+                    {"Compact"}
+                </label>
+                <label title="Include mode and callsign when copying a frequency">
+                    <input type="checkbox" checked={*copy_details} onchange={on_copy_details_toggle} />
+                    {"Copy details"}
+                </label>
+                <label>
+                    <input type="checkbox" checked={*show_map} onchange={on_show_map_toggle} />
+                    {"Map"}
                 </label>
-                        <div class="status">{(*status).clone()}</div>
             </div>
+            { if *show_self_spot {
+                html! {
+                    <div class="self-spot">
+                        <h3>{"Self-spot"}</h3>
+                        <label>{"Callsign "}
+                            <input
+                                type="text"
+                                placeholder="K6EL/P"
+                                value={self_spot_form.callsign.clone()}
+                                oninput={on_self_spot_input(|form, value| form.callsign = value)}
+                            />
+                        </label>
+                        <label>{"Summit "}
+                            <input
+                                type="text"
+                                placeholder="W7W/LC-001"
+                                value={self_spot_form.summit.clone()}
+                                oninput={on_self_spot_input(|form, value| form.summit = value)}
+                            />
+                        </label>
+                        <label>{"MHz "}
+                            <input
+                                type="text"
+                                inputmode="decimal"
+                                class="freq-input"
+                                value={self_spot_form.frequency.clone()}
+                                oninput={on_self_spot_input(|form, value| form.frequency = value)}
+                            />
+                        </label>
+                        <label>{"Mode "}
+                            <select onchange={on_self_spot_mode_change}>
+                                { for SELF_SPOT_MODES.iter().map(|&mode| html! {
+                                    <option value={mode} selected={self_spot_form.mode == mode}>{ mode }</option>
+                                }) }
+                            </select>
+                        </label>
+                        <label>{"Comment "}
+                            <input
+                                type="text"
+                                maxlength={MAX_SPOT_COMMENT.to_string()}
+                                value={self_spot_form.comments.clone()}
+                                oninput={on_self_spot_input(|form, value| form.comments = value)}
+                            />
+                        </label>
+                        <button onclick={on_post_self_spot} disabled={*self_spot_sending}>
+                            { if *self_spot_sending { "Posting..." } else { "Post spot" } }
+                        </button>
+                        { match &*self_spot_reply {
+                            Some(Ok(body)) => html! { <pre class="self-spot-reply">{ body.clone() }</pre> },
+                            Some(Err(err)) => html! { <pre class="self-spot-reply error">{ err.clone() }</pre> },
+                            None => html! {},
+                        } }
+                    </div>
+                }
+            } else { html! {} } }
             { if *show_settings {
                 html! {
                     <div class="settings-panel">
                         <h3>{"Settings"}</h3>
+                        <label>{"Radio: "}
+                            <select onchange={on_rig_change}>
+                                { for Rig::ALL.iter().map(|r| html! {
+                                    <option value={r.key()} selected={*r == *rig}>{ r.label() }</option>
+                                }) }
+                            </select>
+                        </label>
+                        { if kenwood {
+                            html! {
+                                <label>{"Model: "}
+                                    <select onchange={on_kenwood_model_change}>
+                                        { for KenwoodModel::ALL.iter().map(|m| html! {
+                                            <option value={m.key()} selected={*m == *kenwood_model}>{ m.label() }</option>
+                                        }) }
+                                    </select>
+                                </label>
+                            }
+                        } else { html! {} } }
+                        <label>{"Baud rate: "}
+                            <select onchange={on_baud_change}>
+                                { for BAUD_RATES.iter().map(|rate| html! {
+                                    <option value={rate.to_string()} selected={*rate == *baud_rate}>{ rate }</option>
+                                }) }
+                            </select>
+                        </label>
+                        <div class="manual-tune">
+                            <label>{"Tune to (MHz): "}
+                                <input type="text" inputmode="decimal" value={(*manual_freq).clone()} oninput={on_manual_freq_input} />
+                            </label>
+                            <select onchange={on_manual_mode_change}>
+                                <option value="" selected={manual_mode.is_empty()}>{"Band's last mode"}</option>
+                                { for MANUAL_MODES.iter().map(|m| html! {
+                                    <option value={*m} selected={*m == manual_mode.as_str()}>{ *m }</option>
+                                }) }
+                            </select>
+                            <button onclick={on_manual_tune}>{"Tune"}</button>
+                        </div>
+                        { if tune_history.borrow().is_empty() { html! {} } else {
+                            html! {
+                                <div class="tune-history">
+                                    {"Recent: "}
+                                    { for tune_history.borrow().iter().map(|(mhz, mode)| {
+                                        let tune_to = tune_to.clone();
+                                        let entry = (*mhz, mode.clone());
+                                        html! {
+                                            <button class="history-chip" onclick={Callback::from(move |_| tune_to.emit(entry.clone()))}>
+                                                { format!("{:.4} {}", mhz, mode) }
+                                            </button>
+                                        }
+                                    }) }
+                                </div>
+                            }
+                        } }
                         <label>{"Raw CAT command (text): "}
                             <input type="text" value={(*raw_cmd).clone()} oninput={on_raw_input} />
                         </label>
                         <button onclick={on_send_raw}>{"Send Raw"}</button>
+                        <button onclick={on_read_once} title="Read one reply, even with the background reader stopped">{"Read"}</button>
+                        <label title="Skip validation and send the field exactly as typed">
+                            <input type="checkbox" checked={*raw_force} onchange={on_raw_force_toggle} />
+                            {"Force (send unchecked)"}
+                        </label>
+                        <label>{"Command sequence: "}
+                            <input
+                                type="text"
+                                placeholder="FA00014062000;MD3;FW0200;"
+                                value={(*cat_macro).clone()}
+                                oninput={on_macro_input}
+                            />
+                        </label>
+                        <button onclick={on_send_macro}>{"Send Sequence"}</button>
+                        <label>{"Segment rules (one per line: LOW-HIGH MODES...):"}
+                            <textarea
+                                class="segment-rules"
+                                rows="3"
+                                placeholder="14.000-14.070 CW FT8"
+                                value={(*segment_rules_text).clone()}
+                                oninput={on_segment_rules_input}
+                                onchange={on_segment_rules_change}
+                            />
+                        </label>
+                        <label>
+                            <input type="checkbox" checked={*poll_tx} onchange={on_poll_tx_toggle} />
+                            {"Poll TX/RX state for the ON AIR indicator (sends IF; every second)"}
+                        </label>
+                        <label>
+                            <input type="checkbox" checked={*split_enabled} onchange={on_split_toggle} />
+                            {"Tune split from comment hints (up 1, QSX ...)"}
+                        </label>
+                        <label>
+                            <input type="checkbox" checked={*verify_tune_enabled} onchange={on_verify_toggle} />
+                            {"Read back frequency and mode after tuning"}
+                        </label>
+                        <label>
+                            <input type="checkbox" checked={*digital_dial_enabled} onchange={on_digital_dial_toggle} />
+                            {"Tune FT8/FT4 spots to the band's dial frequency"}
+                        </label>
+                        <label>
+                            <input type="checkbox" checked={*tune_filter_enabled} onchange={on_tune_filter_toggle} />
+                            {"Set filter width after tuning (Kenwood)"}
+                        </label>
+                        <label>{"CW width (Hz): "}
+                            <input type="number" min="50" step="50" value={cw_width.to_string()} onchange={on_cw_width_change} />
+                        </label>
+                        <label>{"SSB width (Hz): "}
+                            <input type="number" min="1000" step="100" value={ssb_width.to_string()} onchange={on_ssb_width_change} />
+                        </label>
+                        <label>
+                            <input type="checkbox" checked={*band_mode_memory} onchange={on_band_mode_memory_toggle} />
+                            {"Remember the last mode used on each band"}
+                        </label>
+                        <label>
+                            <input type="checkbox" checked={*compact_hide_comments} onchange={on_compact_hide_comments_toggle} />
+                            {"Hide comments in compact view"}
+                        </label>
+                        <label>{"My grid locator: "}
+                            <input
+                                type="text"
+                                placeholder="CN87ts"
+                                maxlength="6"
+                                value={(*my_grid).clone()}
+                                oninput={on_my_grid_input}
+                            />
+                        </label>
+                        <label>{"My summit (for S2S): "}
+                            <input
+                                type="text"
+                                placeholder="W7W/KG-001"
+                                value={(*my_summit).clone()}
+                                oninput={on_my_summit_input}
+                            />
+                        </label>
+                        <label>{"Watchlist (comma-separated callsigns): "}
+                            <input type="text" value={(*watchlist).clone()} oninput={on_watchlist_input} />
+                        </label>
+                        <label>
+                            <input type="checkbox" checked={*watch_beep} onchange={on_watch_beep_toggle} />
+                            {"Beep when a watched callsign appears"}
+                        </label>
+                        <label>
+                            <input type="checkbox" checked={*alert_muted} onchange={on_alert_mute_toggle} />
+                            {"Mute the alert for new spots matching the filters"}
+                        </label>
+                        <label>{"Dim spots older than (min): "}
+                            <input
+                                type="number"
+                                min="1"
+                                step="1"
+                                value={stale_minutes.to_string()}
+                                oninput={on_stale_change}
+                            />
+                        </label>
+                        <label>{"Warn when spots are within (kHz): "}
+                            <input
+                                type="text"
+                                inputmode="decimal"
+                                value={clash_khz.to_string()}
+                                oninput={on_clash_change}
+                            />
+                        </label>
+                        <label>{"Spots to fetch: "}
+                            <input
+                                type="number"
+                                min="1"
+                                step="1"
+                                value={spot_count.to_string()}
+                                oninput={on_spot_count_change}
+                            />
+                        </label>
+                        <label>{"Spots API: "}
+                            <input type="url" value={(*spots_api).clone()} oninput={on_spots_api_change} />
+                        </label>
+                        <label>{"SOTA API token for self-spotting (kept for this session only): "}
+                            <input
+                                type="password"
+                                autocomplete="off"
+                                value={(*spot_token).clone()}
+                                oninput={on_spot_token_input}
+                            />
+                        </label>
+                        <label>{ format!("Refresh every (s, {}–{}): ", MIN_REFRESH_SECS, MAX_REFRESH_SECS) }
+                            <input
+                                type="number"
+                                min={MIN_REFRESH_SECS.to_string()}
+                                max={MAX_REFRESH_SECS.to_string()}
+                                step="10"
+                                value={refresh_secs.to_string()}
+                                onchange={on_refresh_secs_change}
+                            />
+                        </label>
+                        <label>{ format!("Delay between CAT commands (ms, {}–{}): ", MIN_COMMAND_DELAY_MS, MAX_COMMAND_DELAY_MS) }
+                            <input
+                                type="number"
+                                min={MIN_COMMAND_DELAY_MS.to_string()}
+                                max={MAX_COMMAND_DELAY_MS.to_string()}
+                                step="10"
+                                value={command_delay.to_string()}
+                                onchange={on_command_delay_change}
+                            />
+                        </label>
+                        <label>{"Merge repeat spots within (min): "}
+                            <input
+                                type="number"
+                                min="0"
+                                step="1"
+                                value={dedup_minutes.borrow().to_string()}
+                                oninput={on_dedup_change}
+                            />
+                        </label>
+                        <div class="columns">
+                            <h4>{"Columns"}</h4>
+                            { for Column::ALL.into_iter().map(|column| html! {
+                                <label>
+                                    <input
+                                        type="checkbox"
+                                        checked={shown(column)}
+                                        onchange={on_column_toggle(column)}
+                                    />
+                                    { column.label() }
+                                </label>
+                            }) }
+                        </div>
                         <div class="response-log">
                             <h4>{"Response Log"}</h4>
+                            <label>
+                                <input type="checkbox" checked={*log_tx} onchange={on_log_tx_toggle} />
+                                {"Log sent commands"}
+                            </label>
+                            <button onclick={on_copy_log}>{"Copy log"}</button>
+                            <button onclick={on_clear_log}>{"Clear log"}</button>
+                            <label>
+                                <input type="checkbox" checked={*session_mode} onchange={on_session_toggle} />
+                                {"Session mode: keep reading while settings are closed (holds the reader lock)"}
+                            </label>
                             <div class="last-rx">{ format!("Last RX: {}", (*last_rx).clone()) }</div>
-                            { for (*response_log).iter().map(|line| html!{ <div class="resp">{ line.clone() }</div> }) }
+                            { for serial.log_entries().into_iter().map(|entry| html! {
+                                <div class={classes!("resp", entry.direction.label().to_lowercase())}>
+                                    <span class="log-time">{ entry.time }</span>
+                                    { format!(" {}: {}", entry.direction.label(), entry.text) }
+                                </div>
+                            }) }
                         </div>
                         <hr/>
                         <div class="std-commands">
                             <h4>{"Standard Commands"}</h4>
                             <button onclick={on_vfo_a}>{"VFO A"}</button>
                             <button onclick={on_vfo_b}>{"VFO B"}</button>
+                            { if kenwood {
+                                html! {
+                                    <button onclick={on_ping} title="Sends ID; only reads, never transmits">{"Ping radio"}</button>
+                                }
+                            } else { html! {} } }
                             <button onclick={on_query_freq}>{"Query Frequency"}</button>
+                            { if kenwood {
+                                html! {
+                                    <>
+                                        <button onclick={on_query_smeter}>{"S-meter"}</button>
+                                        { match *smeter {
+                                            Some(raw) => html! {
+                                                <span class="smeter">
+                                                    <meter min="0" max="30" high="15" value={raw.to_string()}></meter>
+                                                    { KenwoodDriver::smeter_label(raw) }
+                                                </span>
+                                            },
+                                            None => html! {},
+                                        } }
+                                        <button onclick={on_query_split}>{"Query Split"}</button>
+                                        { match *split_state {
+                                            Some(state) => html! { <div class="split-state">{ format_split(&state) }</div> },
+                                            None => html! {},
+                                        } }
+                                    </>
+                                }
+                            } else { html! {} } }
                             <div class="modes">
                                 <button onclick={
                                     {
@@ -540,42 +4947,819 @@ pub fn app() -> Html {
                                 }>{"AM"}</button>
                             </div>
                         </div>
+                        { if kenwood {
+                            html! {
+                                <>
+                                    <button class="atu" onclick={on_atu_tune} title="Sends AC111; the radio will transmit">{"ATU Tune"}</button>
+                                    <div class="rit">
+                                        <h4>{"RIT"}</h4>
+                                        <label>
+                                            <input type="checkbox" checked={*rit_enabled} onchange={on_rit_toggle} />
+                                            {"On"}
+                                        </label>
+                                        { for [-RIT_BUTTON_HZ, RIT_BUTTON_HZ].into_iter().map(|delta| {
+                                            let cb = on_rit_step.clone();
+                                            html! {
+                                                <button onclick={Callback::from(move |_| cb.emit(delta))}>{ format!("{:+} Hz", delta) }</button>
+                                            }
+                                        }) }
+                                        <button onclick={on_rit_clear}>{"Clear"}</button>
+                                        <span class="rit-offset">{ format!("{:+} Hz", *rit_offset) }</span>
+                                    </div>
+                                    <div class="filter-width">
+                                        <h4>{"Filter width"}</h4>
+                                        { for [("CW", CW_WIDTH_PRESETS), ("SSB", SSB_WIDTH_PRESETS)].into_iter().map(|(family, presets)| html! {
+                                            <span class="width-presets">
+                                                { format!("{}: ", family) }
+                                                { for presets.iter().map(|&width| {
+                                                    let cb = on_set_filter_width.clone();
+                                                    html! {
+                                                        <button onclick={Callback::from(move |_| cb.emit(width))}>{ format!("{} Hz", width) }</button>
+                                                    }
+                                                }) }
+                                            </span>
+                                        }) }
+                                        <button onclick={on_query_filter_width}>{"Query Width"}</button>
+                                        { match *filter_width {
+                                            Some(width) => html! { <span class="filter-width-value">{ format!("{} Hz", width) }</span> },
+                                            None => html! {},
+                                        } }
+                                    </div>
+                                    <div class="noise">
+                                        <h4>{"Noise"}</h4>
+                                        { {
+                                            let cb = on_noise_blanker.clone();
+                                            let on = *noise_blanker == Some(true);
+                                            html! {
+                                                <button
+                                                    class={classes!("toggle", on.then_some("active"))}
+                                                    onclick={Callback::from(move |_| cb.emit(!on))}
+                                                >{"NB"}</button>
+                                            }
+                                        } }
+                                        { for [(0_u8, "NR off"), (1, "NR1"), (2, "NR2")].into_iter().map(|(level, label)| {
+                                            let cb = on_noise_reduction.clone();
+                                            html! {
+                                                <button
+                                                    class={classes!("toggle", (*noise_reduction == Some(level)).then_some("active"))}
+                                                    onclick={Callback::from(move |_| cb.emit(level))}
+                                                >{ label }</button>
+                                            }
+                                        }) }
+                                        <button onclick={on_query_noise}>{"Query"}</button>
+                                    </div>
+                                    <div class="agc">
+                                        <h4>{"AGC"}</h4>
+                                        { for AgcSpeed::ALL.into_iter().map(|speed| {
+                                            let cb = on_agc.clone();
+                                            html! {
+                                                <button
+                                                    class={classes!("toggle", (*agc == Some(speed)).then_some("active"))}
+                                                    onclick={Callback::from(move |_| cb.emit(speed))}
+                                                >{ speed.label() }</button>
+                                            }
+                                        }) }
+                                        <button onclick={on_query_agc}>{"Query AGC"}</button>
+                                        { match *agc {
+                                            Some(speed) => html! { <span class="agc-value">{ format!("AGC {}", speed.label()) }</span> },
+                                            None => html! {},
+                                        } }
+                                    </div>
+                                    <div class="keyer">
+                                        <h4>{"Keyer"}</h4>
+                                        <label>{ format!("Speed (WPM, {}–{}): ", KEYER_MIN_WPM, KEYER_MAX_WPM) }
+                                            <input
+                                                type="number"
+                                                min={KEYER_MIN_WPM.to_string()}
+                                                max={KEYER_MAX_WPM.to_string()}
+                                                step="1"
+                                                value={keyer_wpm.to_string()}
+                                                onchange={on_keyer_speed_change}
+                                            />
+                                        </label>
+                                        <button onclick={on_query_keyer_speed}>{"Query Speed"}</button>
+                                    </div>
+                                    <div class="power">
+                                        <h4>{"Power"}</h4>
+                                        { for [5_u32, 50, 100].into_iter().map(|watts| {
+                                            let cb = on_set_power.clone();
+                                            html! {
+                                                <button onclick={Callback::from(move |_| cb.emit(watts))}>{ format!("{} W", watts) }</button>
+                                            }
+                                        }) }
+                                        <button onclick={on_query_power}>{"Query Power"}</button>
+                                        <div class="power-state">
+                                            <button onclick={on_power_on} title="Wake the radio from standby (PS1;)">{"Power on"}</button>
+                                            <button onclick={on_power_off} title="Put the radio in standby (PS0;)">{"Standby"}</button>
+                                            <button onclick={on_query_power_state}>{"Query state"}</button>
+                                            { match *radio_power {
+                                                Some(true) => html! { <span class="power-value">{"On"}</span> },
+                                                Some(false) => html! { <span class="power-value">{"Standby"}</span> },
+                                                None => html! {},
+                                            } }
+                                        </div>
+                                    </div>
+                                </>
+                            }
+                        } else { html! {} } }
+                        <div class="session-stats">
+                            <h4>{"Session"}</h4>
+                            { match serial.session_stats() {
+                                stats @ SessionStats { connected_at_ms: Some(since), .. } if *connected => html! {
+                                    <dl>
+                                        <dt>{"Connected for"}</dt><dd>{ format_uptime(now_ms - since) }</dd>
+                                        <dt>{"Commands sent"}</dt><dd>{ stats.commands_sent }</dd>
+                                        <dt>{"Frames received"}</dt><dd>{ stats.frames_received }</dd>
+                                        <dt>{"Errors"}</dt><dd>{ stats.errors }</dd>
+                                    </dl>
+                                },
+                                _ => html! { <span class="session-idle">{"Not connected"}</span> },
+                            } }
+                        </div>
+                        <div class="debug">
+                            <h4>{"Debug"}</h4>
+                            <label>
+                                <input type="checkbox" checked={raw_response.borrow().is_some()} onchange={on_raw_response_toggle} />
+                                {"Show raw API response"}
+                            </label>
+                            { match raw_response.borrow().as_deref() {
+                                Some("") => html! { <p class="raw-response-empty">{"Waiting for the next refresh..."}</p> },
+                                Some(raw) => html! { <pre class="raw-response">{ raw }</pre> },
+                                None => html! {},
+                            } }
+                        </div>
+                        <div class="backup">
+                            <h4>{"Backup"}</h4>
+                            <button onclick={on_export_settings}>{"Export settings"}</button>
+                            <label>{"Import settings: "}
+                                <input type="file" accept="application/json,.json" onchange={on_import_settings} />
+                            </label>
+                        </div>
                         <hr/>
-                        <button onclick={on_test_14062}>{"14.062 CW"}</button>
+                        { if kenwood {
+                            html! {
+                                <button onclick={on_test_14062}>{"14.062 CW"}</button>
+                            }
+                        } else { html! {} } }
                     </div>
                 }
             } else { html!{} } }
-            <table>
+            <div class="spot-summary">
+                { format!("Showing {} of {} spots", visible_spots.len(), spots.len()) }
+                { if comment_filter.trim().is_empty() {
+                    html! {}
+                } else {
+                    let matching = spots.iter().filter(|s| comment_matches(&s.comments, &comment_filter)).count();
+                    html! { { format!(" · {} with \"{}\" in the comments", matching, comment_filter.trim()) } }
+                } }
+            </div>
+            { if *show_map {
+                let markers = visible_spots
+                    .iter()
+                    .filter_map(|&(idx, spot)| {
+                        let latlon = summits.borrow().get(&spot.summit).cloned().flatten()?.latlon()?;
+                        Some(MapMarker {
+                            idx,
+                            latlon,
+                            band: band_for_freq(spot.frequency_mhz),
+                            title: format!("{} {} {:.4} {}", spot.callsign, spot.summit, spot.frequency_mhz, spot.mode),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                // Selecting from the map brings the spot's page and row into
+                // view, and tunes only when asked to.
+                let on_select = {
+                    let highlighted = highlighted.clone();
+                    let page = page.clone();
+                    let on_tune = on_tune.clone();
+                    let map_tunes = *map_tunes;
+                    let order = visible_spots.iter().map(|&(idx, _)| idx).collect::<Vec<_>>();
+                    Callback::from(move |idx: usize| {
+                        if let Some(position) = order.iter().position(|&i| i == idx) {
+                            page.set(position / PAGE_SIZE);
+                        }
+                        highlighted.set(Some(idx));
+                        if map_tunes {
+                            on_tune.emit(idx);
+                        }
+                    })
+                };
+                html! {
+                    <div class="map-panel">
+                        { render_spot_map(&markers, my_qth, *highlighted, &on_select) }
+                        <div class="map-footer">
+                            { format!("{} of {} spots located", markers.len(), visible_spots.len()) }
+                            <label>
+                                <input type="checkbox" checked={*map_tunes} onchange={on_map_tunes_toggle} />
+                                {"Tune when a marker is clicked"}
+                            </label>
+                        </div>
+                    </div>
+                }
+            } else { html! {} } }
+            <table class={classes!((*compact).then_some("compact"))} aria-label="Spots">
                 <thead>
                     <tr>
-                        <th>{"Time"}</th>
-                        <th>{"Callsign"}</th>
-                        <th>{"Summit"}</th>
-                        <th class="freq">{"Frequency"}</th>
-                        <th>{"Mode"}</th>
-                        <th>{"Comments"}</th>
+                        <th class="star" title="Favorite summits">{"★"}</th>
+                        <th class="worked-cell" title="Worked">{"✓"}</th>
+                        { if shown(Column::Time) { sort_header(SortColumn::Time, "Time", "") } else { html! {} } }
+                        { if shown(Column::Age) { html! { <th>{"Age"}</th> } } else { html! {} } }
+                        { if shown(Column::Source) { html! { <th>{"Source"}</th> } } else { html! {} } }
+                        { if shown(Column::Callsign) { sort_header(SortColumn::Callsign, "Callsign", "") } else { html! {} } }
+                        { if shown(Column::Summit) { sort_header(SortColumn::Summit, "Summit", "") } else { html! {} } }
+                        { if shown(Column::Frequency) { sort_header(SortColumn::Frequency, "Frequency", "freq") } else { html! {} } }
+                        { if shown(Column::Band) { html! { <th>{"Band"}</th> } } else { html! {} } }
+                        { if show_bearing { html! { <th title="Bearing and distance from your grid">{"Bearing"}</th> } } else { html! {} } }
+                        { if shown(Column::Mode) { sort_header(SortColumn::Mode, "Mode", "") } else { html! {} } }
+                        { if show_comments { html! { <th>{"Comments"}</th> } } else { html! {} } }
                     </tr>
                 </thead>
                 <tbody>
-                    { for spots.iter().enumerate().map(|(idx, spot)| {
-                        let row_class = if Some(idx) == *selected_row { "tuned" } else { "" };
+                    { if visible_spots.is_empty() {
+                        let message = if !spots.is_empty() {
+                            "No spots match your filters"
+                        } else if *spots_loaded {
+                            "No spots posted right now; new ones appear on the next refresh"
+                        } else {
+                            "No spots yet, refreshing..."
+                        };
+                        html! {
+                            <tr class="empty-state">
+                                <td colspan={column_count.to_string()}>{ message }</td>
+                            </tr>
+                        }
+                    } else { html! {} } }
+                    // Rows keep their index into `spots`, so click-to-tune is
+                    // unaffected by which page is showing.
+                    { for visible_spots[page_start..page_end].iter().map(|&(idx, spot)| {
+                        let age_ms = spot.epoch_ms.map(|ts| now_ms - ts);
+                        let stale = age_ms.is_some_and(|age| age > *stale_minutes * 60_000.0);
+                        let is_worked = worked.contains(&spot.worked_key());
+                        let row_class = classes!(
+                            (Some(idx) == *selected_row).then_some("tuned"),
+                            (Some(idx) == *highlighted).then_some("highlighted"),
+                            stale.then_some("stale"),
+                            is_watched(&watchlist, &spot.callsign).then_some("watched"),
+                            is_favorite(spot).then_some("favorite"),
+                            is_worked.then_some("worked"),
+                            is_s2s_candidate(spot, &my_summit).then_some("s2s"),
+                        );
+                        let on_worked_click = {
+                            let on_worked_toggle = on_worked_toggle.clone();
+                            let key = spot.worked_key();
+                            Callback::from(move |e: MouseEvent| {
+                                e.stop_propagation();
+                                on_worked_toggle.emit(key.clone());
+                            })
+                        };
+                        let on_star_click = {
+                            let on_favorite_toggle = on_favorite_toggle.clone();
+                            let summit = spot.summit.clone();
+                            Callback::from(move |e: MouseEvent| {
+                                e.stop_propagation();
+                                on_favorite_toggle.emit(summit.clone());
+                            })
+                        };
+                        let on_copy_click = {
+                            let on_copy_spot = on_copy_spot.clone();
+                            let text = spot_copy_text(spot, *copy_details);
+                            Callback::from(move |e: MouseEvent| {
+                                e.stop_propagation();
+                                on_copy_spot.emit(text.clone());
+                            })
+                        };
                         let on_row_click = {
                             let on_tune = on_tune.clone();
-                            Callback::from(move |_| on_tune.emit(idx))
+                            let highlighted = highlighted.clone();
+                            Callback::from(move |_| {
+                                highlighted.set(Some(idx));
+                                on_tune.emit(idx);
+                            })
+                        };
+                        // Enter or Space on the row itself tunes; keys aimed at
+                        // the buttons inside it are left to them.
+                        let on_row_key = {
+                            let on_tune = on_tune.clone();
+                            let highlighted = highlighted.clone();
+                            Callback::from(move |e: KeyboardEvent| {
+                                // Yew dispatches from the app's root element, so
+                                // `current_target` is never the row; check the
+                                // target itself.
+                                let on_row = e
+                                    .target()
+                                    .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+                                    .is_some_and(|el| el.tag_name() == "TR");
+                                if !on_row || !activates_row(&e.key()) {
+                                    return;
+                                }
+                                e.prevent_default();
+                                e.stop_propagation();
+                                highlighted.set(Some(idx));
+                                on_tune.emit(idx);
+                            })
+                        };
+                        let on_row_focus = {
+                            let highlighted = highlighted.clone();
+                            Callback::from(move |_: FocusEvent| {
+                                if *highlighted != Some(idx) {
+                                    highlighted.set(Some(idx));
+                                }
+                            })
                         };
                         html! {
-                            <tr class={row_class} onclick={on_row_click}>
-                                <td>{ format_time(&spot.timestamp) }</td>
-                                <td>{ spot.callsign.clone() }</td>
-                                <td>{ spot.summit.clone() }</td>
-                                <td class="freq">{ format!("{:.4}", spot.frequency_mhz) }</td>
-                                <td>{ spot.mode.clone() }</td>
-                                <td>{ spot.comments.clone() }</td>
+                            <tr
+                                id={format!("spot-row-{}", idx)}
+                                class={row_class}
+                                tabindex="0"
+                                aria-label={spot_aria_label(spot)}
+                                aria-current={(Some(idx) == *selected_row).then_some("true")}
+                                onclick={on_row_click}
+                                onkeydown={on_row_key}
+                                onfocus={on_row_focus}
+                            >
+                                <td class="star">
+                                    <button
+                                        class={classes!("star-toggle", is_favorite(spot).then_some("starred"))}
+                                        title="Star this summit"
+                                        aria-label={format!("Star {}", spot.summit)}
+                                        aria-pressed={is_favorite(spot).to_string()}
+                                        onclick={on_star_click}
+                                    >{ if is_favorite(spot) { "★" } else { "☆" } }</button>
+                                </td>
+                                <td class="worked-cell">
+                                    <button
+                                        class={classes!("worked-toggle", is_worked.then_some("done"))}
+                                        title="Mark as worked"
+                                        aria-label={format!("Mark {} as worked", spot.callsign)}
+                                        aria-pressed={is_worked.to_string()}
+                                        onclick={on_worked_click}
+                                    >{"✓"}</button>
+                                </td>
+                                { if shown(Column::Time) {
+                                    html! {
+                                        <td>{ format_time(&spot.timestamp) }</td>
+                                    }
+                                } else { html! {} } }
+                                { if shown(Column::Age) {
+                                    html! {
+                                        <td class="age">{ age_ms.map(format_age).unwrap_or_default() }</td>
+                                    }
+                                } else { html! {} } }
+                                { if shown(Column::Source) {
+                                    html! {
+                                        <td class="source">{ spot.source.clone() }</td>
+                                    }
+                                } else { html! {} } }
+                                { if shown(Column::Callsign) {
+                                    html! {
+                                        <td>
+                                            { spot.callsign.clone() }
+                                            { if spot.merged > 0 {
+                                                html! {
+                                                    <span class="badge" title={format!("{} repeat spots merged", spot.merged)}>
+                                                        { format!("+{}", spot.merged) }
+                                                    </span>
+                                                }
+                                            } else { html! {} } }
+                                        </td>
+                                    }
+                                } else { html! {} } }
+                                { if shown(Column::Summit) {
+                                    html! {
+                                        <td>
+                                            { spot.summit.clone() }
+                                            { match summits.borrow().get(&spot.summit) {
+                                                Some(Some(info)) => html! {
+                                                    <span class="summit-name">
+                                                        { info.name.clone().unwrap_or_default() }
+                                                        { info.points.map(|p| format!(" · {} pt", p)).unwrap_or_default() }
+                                                    </span>
+                                                },
+                                                _ => html! {},
+                                            } }
+                                        </td>
+                                    }
+                                } else { html! {} } }
+                                { if shown(Column::Frequency) {
+                                    html! {
+                                        <td class="freq">
+                                            <button class="copy-freq" title="Copy frequency without tuning" aria-label="Copy frequency" onclick={on_copy_click}>{"⧉"}</button>
+                                            { format!("{:.4}", spot.frequency_mhz) }
+                                            { match clashing_calls(spot, &visible_spots, *clash_khz).as_slice() {
+                                                [] => html! {},
+                                                calls => html! {
+                                                    <span class="clash" title={format!("Within {} kHz of {}", *clash_khz, calls.join(", "))}>{"⚠"}</span>
+                                                },
+                                            } }
+                                        </td>
+                                    }
+                                } else { html! {} } }
+                                { if shown(Column::Band) {
+                                    html! {
+                                        <td>{
+                                            match band_for_freq(spot.frequency_mhz) {
+                                                Some(band) => html! {
+                                                    <span class={classes!("band-chip", format!("band-{}", band))}>{ band }</span>
+                                                },
+                                                None => html! { <span class="band-chip" title="Outside the amateur bands">{"?"}</span> },
+                                            }
+                                        }</td>
+                                    }
+                                } else { html! {} } }
+                                { match my_qth.filter(|_| show_bearing) {
+                                    Some(qth) => {
+                                        let summit = summits.borrow().get(&spot.summit).cloned().flatten();
+                                        html! {
+                                            <td class="bearing">{
+                                                match summit.as_ref().and_then(SummitInfo::latlon) {
+                                                    Some(target) => format!(
+                                                        "{:.0}° {:.0} km",
+                                                        bearing_deg(qth, target),
+                                                        distance_km(qth, target)
+                                                    ),
+                                                    None => String::new(),
+                                                }
+                                            }</td>
+                                        }
+                                    }
+                                    None => html! {},
+                                } }
+                                { if shown(Column::Mode) {
+                                    html! {
+                                        <td>{ spot.mode.clone() }</td>
+                                    }
+                                } else { html! {} } }
+                                { if show_comments {
+                                    html! { <td>{ render_comments(spot, &tune_to) }</td> }
+                                } else { html! {} } }
                             </tr>
                         }
                     }) }
                 </tbody>
             </table>
+            { if page_count > 1 {
+                let go_to = |target: usize| {
+                    let page = page.clone();
+                    Callback::from(move |_| page.set(target))
+                };
+                html! {
+                    <div class="pager">
+                        <button onclick={go_to(current_page.saturating_sub(1))} disabled={current_page == 0}>{"‹ Prev"}</button>
+                        <span>{ format!("Page {} of {}", current_page + 1, page_count) }</span>
+                        <button onclick={go_to(current_page + 1)} disabled={current_page + 1 >= page_count}>{"Next ›"}</button>
+                    </div>
+                }
+            } else { html! {} } }
+            <Toasts status={status.clone()} />
         </div>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_import_keeps_filter_toggles() {
+        let json = r#"{"sotachaser.s2s_only": true, "sotachaser.favorites_only": "false"}"#;
+        let mut entries = settings::parse_import(json, SETTINGS_SCHEMA).unwrap();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![
+                (STORAGE_FAVORITES_ONLY.to_string(), "false".to_string()),
+                (STORAGE_S2S_ONLY.to_string(), "true".to_string()),
+            ]
+        );
+        let mut keys = SETTINGS_SCHEMA.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), SETTINGS_SCHEMA.len());
+    }
+
+    #[test]
+    fn enter_and_space_activate_rows() {
+        assert!(activates_row("Enter"));
+        assert!(activates_row(" "));
+        assert!(!activates_row("Spacebar"));
+        assert!(!activates_row("ArrowDown"));
+        assert!(!activates_row("Tab"));
+    }
+
+    #[test]
+    fn spot_aria_label_names_the_spot() {
+        let mut spot = test_spot("K6EL", 14.062, "CW");
+        assert_eq!(spot_aria_label(&spot), "Tune to K6EL on W7A/MN-001, 14.0620 MHz CW");
+        spot.mode.clear();
+        assert_eq!(spot_aria_label(&spot), "Tune to K6EL on W7A/MN-001, 14.0620 MHz");
+    }
+
+    #[test]
+    fn comment_filter_ignores_case_and_blank_terms() {
+        assert!(comment_matches("QRP 5w from the top", "qrp"));
+        assert!(comment_matches("s2s with W7W/LC-001", "S2S"));
+        assert!(!comment_matches("QRT", "qrp"));
+        assert!(comment_matches("anything", "  "));
+    }
+
+    #[test]
+    fn format_uptime_pads_minutes_and_seconds() {
+        assert_eq!(format_uptime(0.0), "0:00:00");
+        assert_eq!(format_uptime(61_500.0), "0:01:01");
+        assert_eq!(format_uptime(3_723_000.0), "1:02:03");
+    }
+
+    #[test]
+    fn self_spot_form_validates_fields() {
+        let form = SelfSpotForm {
+            callsign: " k6el/p ".to_string(),
+            summit: "w7w/lc-001".to_string(),
+            frequency: "14,062".to_string(),
+            mode: "cw".to_string(),
+            comments: "QRT 15 min".to_string(),
+        };
+        let spot = form.validate().unwrap();
+        assert_eq!(spot.callsign, "K6EL/P");
+        assert_eq!((spot.association.as_str(), spot.summit.as_str()), ("W7W", "LC-001"));
+        assert_eq!(spot.frequency, "14.0620");
+        assert_eq!(spot.mode, "cw");
+
+        let bad = |update: fn(&mut SelfSpotForm)| {
+            let mut form = form.clone();
+            update(&mut form);
+            form.validate().is_err()
+        };
+        assert!(bad(|f| f.callsign = "K/EL".to_string()));
+        assert!(bad(|f| f.summit = "W7W-LC001".to_string()));
+        assert!(bad(|f| f.summit = "W7W/LC-00A".to_string()));
+        assert!(bad(|f| f.frequency = "15.5".to_string()));
+        assert!(bad(|f| f.mode = "RTTY".to_string()));
+        assert!(bad(|f| f.comments = "x".repeat(MAX_SPOT_COMMENT + 1)));
+    }
+
+    #[test]
+    fn toggle_hidden_column_keeps_callsign_or_frequency() {
+        let hidden = toggle_hidden_column(&[], Column::Comments).unwrap();
+        assert_eq!(hidden, vec![Column::Comments]);
+        assert!(toggle_hidden_column(&hidden, Column::Comments).unwrap().is_empty());
+
+        let hidden = toggle_hidden_column(&[Column::Callsign], Column::Frequency);
+        assert_eq!(hidden, None);
+        let hidden = toggle_hidden_column(&[Column::Callsign, Column::Frequency], Column::Callsign);
+        assert_eq!(hidden, Some(vec![Column::Frequency]));
+        assert_eq!(Column::from_key("band"), Some(Column::Band));
+        assert_eq!(Column::from_key("nope"), None);
+    }
+
+    fn raw_spot(json: &str) -> SpotRaw {
+        serde_json::from_str(json).expect("valid spot json")
+    }
+
+    /// A SOTA spot on W7A/MN-001 with no id, time or comment; tests set
+    /// whatever else they care about.
+    fn test_spot(call: &str, mhz: f64, mode: &str) -> Spot {
+        Spot {
+            id: 0,
+            source: SotaSource::LABEL.to_string(),
+            timestamp: String::new(),
+            epoch_ms: None,
+            callsign: call.to_string(),
+            summit: "W7A/MN-001".to_string(),
+            frequency_mhz: mhz,
+            mode: mode.to_string(),
+            comments: String::new(),
+            merged: 0,
+        }
+    }
+
+    #[test]
+    fn from_raw_rejects_null_callsign() {
+        let raw = raw_spot(
+            r#"{"timeStamp":"2024-05-01T12:00:00","activatorCallsign":null,
+                "summitCode":"W7A/MN-001","frequency":"14.062","mode":"CW","comments":""}"#,
+        );
+        assert!(Spot::from_raw(raw).is_none());
+    }
+
+    #[test]
+    fn from_raw_rejects_blank_callsign_and_summit() {
+        let blank_call = raw_spot(
+            r#"{"activatorCallsign":"   ","summitCode":"W7A/MN-001","frequency":"14.062"}"#,
+        );
+        assert!(Spot::from_raw(blank_call).is_none());
+
+        let blank_summit = raw_spot(r#"{"activatorCallsign":"K6EL","summitCode":"","frequency":"14.062"}"#);
+        assert!(Spot::from_raw(blank_summit).is_none());
+    }
+
+    #[test]
+    fn from_raw_normalizes_callsign() {
+        let raw = raw_spot(
+            r#"{"activatorCallsign":" k6el/p ","summitCode":" W7A/MN-001 ","frequency":"14.062"}"#,
+        );
+        let spot = Spot::from_raw(raw).expect("valid spot");
+        assert_eq!(spot.callsign, "K6EL/P");
+        assert_eq!(spot.summit, "W7A/MN-001");
+    }
+
+    #[test]
+    fn dedup_merges_consecutive_repeats_within_window() {
+        let spot = |call: &str, mhz: f64, minute: f64| Spot {
+            epoch_ms: Some(minute * 60_000.0),
+            ..test_spot(call, mhz, "CW")
+        };
+        // Newest first, as the feed sends them; case doesn't matter.
+        let merged = dedup_spots(vec![spot("K6EL", 14.062, 20.0), spot("k6el", 14.062, 10.0)], 10.0);
+        assert_eq!(merged.len(), 1);
+        assert_eq!((merged[0].callsign.as_str(), merged[0].merged), ("K6EL", 1));
+
+        let apart = dedup_spots(vec![spot("K6EL", 14.062, 20.0), spot("K6EL", 14.062, 9.99)], 10.0);
+        assert_eq!(apart.len(), 2);
+
+        let other_band = dedup_spots(vec![spot("K6EL", 14.062, 20.0), spot("K6EL", 7.032, 19.0)], 10.0);
+        assert_eq!(other_band.len(), 2);
+
+        let interrupted = dedup_spots(
+            vec![spot("K6EL", 14.062, 20.0), spot("W7ABC", 14.070, 19.0), spot("K6EL", 14.062, 18.0)],
+            10.0,
+        );
+        assert_eq!(interrupted.len(), 3);
+    }
+
+    #[test]
+    fn spot_frequency_takes_first_numeric_token() {
+        assert_eq!(parse_spot_frequency("14.062"), Some(14.062));
+        assert_eq!(parse_spot_frequency(" 14.062 CW"), Some(14.062));
+        assert_eq!(parse_spot_frequency("7.032 (QSX 7.035)"), Some(7.032));
+        assert_eq!(parse_spot_frequency("14.285USB"), Some(14.285));
+    }
+
+    #[test]
+    fn spot_frequency_accepts_comma_decimal() {
+        assert_eq!(parse_spot_frequency("10,118"), Some(10.118));
+        assert_eq!(parse_spot_frequency("7,032 cw"), Some(7.032));
+    }
+
+    #[test]
+    fn spot_frequency_rejects_empty_and_zero() {
+        assert_eq!(parse_spot_frequency(""), None);
+        assert_eq!(parse_spot_frequency("   "), None);
+        assert_eq!(parse_spot_frequency("CW"), None);
+        assert_eq!(parse_spot_frequency("0"), None);
+        assert_eq!(parse_spot_frequency("0.000"), None);
+    }
+
+    #[test]
+    fn parse_decimal_accepts_both_separators() {
+        assert_eq!(parse_decimal("14.062"), Some(14.062));
+        assert_eq!(parse_decimal(" 14,062 "), Some(14.062));
+        assert_eq!(parse_decimal("14,0,62"), None);
+        assert_eq!(parse_decimal(""), None);
+    }
+
+    #[test]
+    fn listen_hints_cover_common_phrasings() {
+        let hints = find_listen_hints("CW up 1, QSX 14.025 listening on 7035", 14.062);
+        let mhz = hints.iter().map(|h| (h.mhz * 1000.0).round() / 1000.0).collect::<Vec<_>>();
+        assert_eq!(mhz, vec![14.063, 14.025, 7.035]);
+        assert_eq!(&"CW up 1, QSX 14.025 listening on 7035"[hints[1].start..hints[1].end], "QSX 14.025");
+    }
+
+    #[test]
+    fn listen_hints_ignore_unrelated_numbers() {
+        assert!(find_listen_hints("tnx 599 73 de W7A/MN-001", 14.062).is_empty());
+        assert!(find_listen_hints("up 599", 14.062).is_empty());
+    }
+
+    #[test]
+    fn tune_guard_rejects_inverted_range() {
+        assert_eq!(check_tune_guard(14.062, 30.0, 7.0), Err(RANGE_INVERTED.to_string()));
+        assert_eq!(check_tune_guard(14.062, 14.0, 14.0), Err(RANGE_INVERTED.to_string()));
+    }
+
+    #[test]
+    fn tune_guard_checks_window() {
+        assert!(check_tune_guard(14.062, 7.0, 29.7).is_ok());
+        assert!(check_tune_guard(50.313, 7.0, 29.7).is_err());
+    }
+
+    #[test]
+    fn segment_rules_parse_and_block_other_modes() {
+        let rules = parse_segment_rules("# 20m CW end\n14.000-14.070 cw ft8\n\n7,000-7,040 CW").unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[1], SegmentRule { lo_mhz: 7.0, hi_mhz: 7.04, modes: vec!["CW".to_string()] });
+        assert!(check_segment_rules(&rules, 14.062, "CW").is_ok());
+        assert!(check_segment_rules(&rules, 14.062, "USB").is_err());
+        assert!(check_segment_rules(&rules, 14.285, "SSB").is_ok());
+        assert!(parse_segment_rules("14.070-14.000 CW").is_err());
+        assert!(parse_segment_rules("14.000-14.070").is_err());
+        assert!(parse_segment_rules("14.000 CW").is_err());
+    }
+
+    #[test]
+    fn digital_dial_substitutes_audio_offsets() {
+        assert_eq!(digital_dial("FT8", 14.0755), Some(14.074));
+        assert_eq!(digital_dial("ft4", 7.049), Some(7.0475));
+        assert_eq!(digital_dial("FT8", 14.074), None);
+        assert_eq!(digital_dial("FT8", 14.090), None);
+        assert_eq!(digital_dial("CW", 14.0755), None);
+    }
+
+    #[test]
+    fn s2s_mentions_are_whole_words() {
+        assert!(mentions_s2s("s2s from W7W/KG-001"));
+        assert!(mentions_s2s("QRP, S2S!"));
+        assert!(!mentions_s2s("as2s"));
+    }
+
+    #[test]
+    fn grid_to_latlon_finds_square_centre() {
+        let (lat, lon) = grid_to_latlon("CN87").unwrap();
+        assert!((lat - 47.5).abs() < 1e-9 && (lon + 123.0).abs() < 1e-9);
+        let (lat, lon) = grid_to_latlon("jo01aa").unwrap();
+        assert!((lat - 51.0208).abs() < 1e-3 && (lon - 0.0417).abs() < 1e-3);
+        assert_eq!(grid_to_latlon("ZZ99"), None);
+        assert_eq!(grid_to_latlon("CN8"), None);
+    }
+
+    #[test]
+    fn distance_and_bearing_between_points() {
+        let london = (51.5074, -0.1278);
+        let paris = (48.8566, 2.3522);
+        assert!((distance_km(london, paris) - 343.5).abs() < 1.0);
+        assert!((bearing_deg(london, paris) - 148.1).abs() < 0.5);
+        assert!((bearing_deg((0.0, 0.0), (0.0, -10.0)) - 270.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn remap_row_follows_spot_across_refresh() {
+        let spot = |call: &str| test_spot(call, 14.062, "CW");
+        let old = vec![spot("K7A"), spot("K7B")];
+        let new = vec![spot("K7C"), spot("K7A"), spot("K7B")];
+        assert_eq!(remap_row(&old, &new, Some(1)), Some(2));
+        assert_eq!(remap_row(&old, &new[2..], Some(0)), None);
+        assert_eq!(remap_row(&old, &new, None), None);
+    }
+
+    #[test]
+    fn tune_history_skips_repeats_and_caps_length() {
+        let mut history = Vec::new();
+        push_tune_history(&mut history, 14.062, "CW");
+        push_tune_history(&mut history, 14.062, "cw");
+        assert_eq!(history.len(), 1);
+        push_tune_history(&mut history, 7.032, "CW");
+        push_tune_history(&mut history, 14.062, "CW");
+        assert_eq!(history[0], (14.062, "CW".to_string()));
+        assert_eq!(history.len(), 3);
+        for i in 0..20 {
+            push_tune_history(&mut history, 10.0 + i as f64, "CW");
+        }
+        assert_eq!(history.len(), MAX_TUNE_HISTORY);
+    }
+
+    #[test]
+    fn auto_tune_picks_newest_unseen_match() {
+        let spot = |call: &str, ts: f64, mode: &str| Spot {
+            epoch_ms: Some(ts),
+            ..test_spot(call, 14.062, mode)
+        };
+        let spots = vec![
+            spot("K1A", 1_000.0, "CW"),
+            spot("K1B", 5_000.0, "CW"),
+            spot("K1C", 9_000.0, "SSB"),
+        ];
+        let cw = |s: &Spot| s.mode == "CW";
+        let mut seen = HashSet::new();
+        assert_eq!(pick_auto_tune(&spots, &seen, cw), Some(1));
+        seen.insert(spots[1].tune_key());
+        assert_eq!(pick_auto_tune(&spots, &seen, cw), Some(0));
+        seen.insert(spots[0].tune_key());
+        assert_eq!(pick_auto_tune(&spots, &seen, cw), None);
+    }
+
+    #[test]
+    fn copy_text_optionally_adds_mode_and_call() {
+        let spot = Spot::from_raw(raw_spot(
+            r#"{"activatorCallsign":"K6EL/P","summitCode":"W7A/MN-001","frequency":"14.062","mode":"CW"}"#,
+        ))
+        .expect("valid spot");
+        assert_eq!(spot_copy_text(&spot, false), "14.0620");
+        assert_eq!(spot_copy_text(&spot, true), "14.0620 CW K6EL/P");
+    }
+
+    #[test]
+    fn map_point_is_equirectangular() {
+        assert_eq!(map_point((0.0, 0.0)), (180.0, 90.0));
+        assert_eq!(map_point((90.0, -180.0)), (0.0, 0.0));
+        assert_eq!(map_point((-45.0, 120.0)), (300.0, 135.0));
+        assert_eq!(map_point((95.0, 200.0)), (360.0, 0.0));
+    }
+
+    #[test]
+    fn page_bounds_clamps_to_last_page() {
+        assert_eq!(page_bounds(20, 0, 50), (0, 20));
+        assert_eq!(page_bounds(120, 1, 50), (50, 100));
+        assert_eq!(page_bounds(120, 9, 50), (100, 120));
+        assert_eq!(page_bounds(0, 3, 50), (0, 0));
+    }
+}
@@ -1,5 +1,7 @@
 mod app;
 mod serial;
+mod settings;
+mod status;
 
 use wasm_bindgen::prelude::*;
 use yew::Renderer;
@@ -1,17 +1,170 @@
 use js_sys::{Function, Object, Promise, Reflect, Uint8Array};
+use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
 use wasm_bindgen::JsValue;
 use wasm_bindgen_futures::{JsFuture, spawn_local};
 use gloo_timers::future::TimeoutFuture;
 use web_sys::Window;
+use futures::channel::oneshot;
+use futures::future::{self, Either, FutureExt, LocalBoxFuture};
 use futures::lock::Mutex;
+use std::collections::VecDeque;
+
+type DisconnectListener = Closure<dyn FnMut(JsValue)>;
+type DisconnectCallback = std::rc::Rc<dyn Fn()>;
+type LogCallback = std::rc::Rc<dyn Fn()>;
+
+/// How long `query` waits for its answer frame by default.
+pub const DEFAULT_FRAME_TIMEOUT_MS: u32 = 1000;
+/// How long `read_all_frames` waits for unsolicited frames when none are
+/// queued yet.
+const FRAME_POLL_MS: u32 = 50;
+/// Oldest unsolicited frames are dropped beyond this many, so nobody
+/// reading them doesn't grow the queue without bound.
+const MAX_UNSOLICITED_FRAMES: usize = 100;
+
+/// Keyer speeds the TS-570 accepts, in WPM.
+pub const KEYER_MIN_WPM: u32 = 10;
+pub const KEYER_MAX_WPM: u32 = 60;
+
+/// Default pause between commands in a multi-command sequence, giving the
+/// radio time to act on each one; see `set_command_delay`.
+pub const DEFAULT_COMMAND_DELAY_MS: u32 = 80;
+
+/// How long one pump pass waits on `read()` before giving up on this pass.
+/// A quiet radio never resolves the read; without this the pump can't
+/// notice it has been stopped or replaced.
+const READ_IDLE_MS: u32 = 500;
+
+/// How long a radio in standby takes to wake its CAT port on the first
+/// `PS1;` before it can act on the second.
+const POWER_ON_WAKE_MS: u32 = 1_000;
+/// A radio powering up answers `PS;` only once it has finished booting.
+pub const POWER_ON_TIMEOUT_MS: u32 = 3_000;
+
+/// Pause before the single retry of a write that failed on a busy bus.
+const WRITE_RETRY_MS: u32 = 50;
+
+/// Oldest log entries are dropped beyond this many.
+const MAX_LOG_ENTRIES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogDirection {
+    Tx,
+    Rx,
+}
+
+impl LogDirection {
+    pub fn label(self) -> &'static str {
+        match self {
+            LogDirection::Tx => "TX",
+            LogDirection::Rx => "RX",
+        }
+    }
+}
+
+/// One line of the CAT exchange log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    /// Local wall-clock time, as HH:MM:SS.mmm.
+    pub time: String,
+    pub direction: LogDirection,
+    pub text: String,
+}
+
+impl std::fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}: {}", self.time, self.direction.label(), self.text)
+    }
+}
+
+/// A query waiting for the first frame that starts with `prefix`.
+struct FrameWaiter {
+    prefix: String,
+    reply: oneshot::Sender<String>,
+}
+
+/// The waiter `frame` belongs to: the first whose prefix it starts with,
+/// trying prefix-less waiters (which take any frame) only when no query
+/// for that prefix is waiting.
+fn waiter_for(waiters: &[FrameWaiter], frame: &str) -> Option<usize> {
+    waiters
+        .iter()
+        .position(|w| !w.prefix.is_empty() && frame.starts_with(&w.prefix))
+        .or_else(|| waiters.iter().position(|w| w.prefix.is_empty()))
+}
+
+/// Counters for the current connection, reset each time a port opens.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SessionStats {
+    /// When the port was opened, in epoch milliseconds.
+    pub connected_at_ms: Option<f64>,
+    pub commands_sent: u32,
+    pub frames_received: u32,
+    /// Failed writes, unanswered queries and reader failures.
+    pub errors: u32,
+}
+
+/// Held for the duration of a tune sequence; see `try_begin_tune`.
+pub struct TuneGuard(std::rc::Rc<std::cell::Cell<bool>>);
+
+impl Drop for TuneGuard {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
 
 #[derive(Clone, Default)]
 pub struct SerialManager {
     port: std::rc::Rc<std::cell::RefCell<Option<JsValue>>>,
     reader: std::rc::Rc<Mutex<Option<JsValue>>>,
-    buffer: std::rc::Rc<Mutex<String>>,
+    /// A `read()` that outlived `READ_IDLE_MS`. The next pass awaits it
+    /// again instead of issuing a second read, so bytes it resolves with
+    /// aren't lost.
+    pending_read: std::rc::Rc<std::cell::RefCell<Option<Promise>>>,
+    /// A reader being cancelled whose lock hasn't been released yet.
+    stale_reader: std::rc::Rc<std::cell::RefCell<Option<JsValue>>>,
+    /// Raw bytes received but not yet split into `;`-terminated frames.
+    /// Decoding waits for a whole frame so a multibyte character split
+    /// across reads is reassembled first.
+    buffer: std::rc::Rc<Mutex<Vec<u8>>>,
+    /// Held from `getWriter` to `releaseLock`, retry included, so
+    /// concurrent callers write one whole command at a time.
+    write_lock: std::rc::Rc<Mutex<()>>,
     drain_running: std::rc::Rc<std::cell::Cell<bool>>,
+    drain_generation: std::rc::Rc<std::cell::Cell<u32>>,
+    /// The pump is the only task that reads from the port. It hands each
+    /// frame to the oldest query waiting on its prefix, or queues it as
+    /// unsolicited for the read loop. It runs only while a query is waiting
+    /// or the read loop's drain is on, then releases the reader so the
+    /// port isn't held between exchanges.
+    pump_running: std::rc::Rc<std::cell::Cell<bool>>,
+    pump_generation: std::rc::Rc<std::cell::Cell<u32>>,
+    waiters: std::rc::Rc<std::cell::RefCell<Vec<FrameWaiter>>>,
+    unsolicited: std::rc::Rc<std::cell::RefCell<VecDeque<String>>>,
+    /// Set while a tune sequence is writing, so overlapping clicks can't
+    /// interleave their `FA`/`MD` commands.
+    tune_busy: std::rc::Rc<std::cell::Cell<bool>>,
+    /// Called when the open port goes away without `disconnect()`, e.g. the
+    /// USB cable is pulled.
+    on_disconnect: std::rc::Rc<std::cell::RefCell<Option<DisconnectCallback>>>,
+    /// `disconnect` listener registered on `navigator.serial`, kept alive
+    /// here so it can be removed again.
+    disconnect_listener: std::rc::Rc<std::cell::RefCell<Option<DisconnectListener>>>,
+    /// Interleaved TX/RX exchange, shared by every clone of the manager.
+    log: std::rc::Rc<std::cell::RefCell<Vec<LogEntry>>>,
+    /// Whether `write_command` records what it sends.
+    log_tx: std::rc::Rc<std::cell::Cell<bool>>,
+    /// Whether commands that can key the transmitter may be sent; see
+    /// `may_transmit`. Off until the user turns it on.
+    tx_enabled: std::rc::Rc<std::cell::Cell<bool>>,
+    /// Which Kenwood CAT dialect to speak; see `KenwoodModel`.
+    kenwood_model: std::rc::Rc<std::cell::Cell<KenwoodModel>>,
+    /// Pause between the commands of a tune or macro sequence, in ms.
+    command_delay_ms: std::rc::Rc<std::cell::Cell<u32>>,
+    /// Called after every change to the log so the UI can redraw.
+    on_log: std::rc::Rc<std::cell::RefCell<Option<LogCallback>>>,
+    stats: std::rc::Rc<std::cell::Cell<SessionStats>>,
 }
 
 impl SerialManager {
@@ -19,12 +172,179 @@ impl SerialManager {
         Self {
             port: std::rc::Rc::new(std::cell::RefCell::new(None)),
             reader: std::rc::Rc::new(Mutex::new(None)),
-            buffer: std::rc::Rc::new(Mutex::new(String::new())),
+            pending_read: std::rc::Rc::new(std::cell::RefCell::new(None)),
+            stale_reader: std::rc::Rc::new(std::cell::RefCell::new(None)),
+            buffer: std::rc::Rc::new(Mutex::new(Vec::new())),
+            write_lock: std::rc::Rc::new(Mutex::new(())),
             drain_running: std::rc::Rc::new(std::cell::Cell::new(false)),
+            drain_generation: std::rc::Rc::new(std::cell::Cell::new(0)),
+            pump_running: std::rc::Rc::new(std::cell::Cell::new(false)),
+            pump_generation: std::rc::Rc::new(std::cell::Cell::new(0)),
+            waiters: std::rc::Rc::new(std::cell::RefCell::new(Vec::new())),
+            unsolicited: std::rc::Rc::new(std::cell::RefCell::new(VecDeque::new())),
+            tune_busy: std::rc::Rc::new(std::cell::Cell::new(false)),
+            on_disconnect: std::rc::Rc::new(std::cell::RefCell::new(None)),
+            disconnect_listener: std::rc::Rc::new(std::cell::RefCell::new(None)),
+            log: std::rc::Rc::new(std::cell::RefCell::new(Vec::new())),
+            log_tx: std::rc::Rc::new(std::cell::Cell::new(true)),
+            tx_enabled: std::rc::Rc::new(std::cell::Cell::new(false)),
+            kenwood_model: std::rc::Rc::new(std::cell::Cell::new(KenwoodModel::default())),
+            command_delay_ms: std::rc::Rc::new(std::cell::Cell::new(DEFAULT_COMMAND_DELAY_MS)),
+            on_log: std::rc::Rc::new(std::cell::RefCell::new(None)),
+            stats: std::rc::Rc::new(std::cell::Cell::new(SessionStats::default())),
+        }
+    }
+
+    /// Register the callback invoked when the port disappears unexpectedly.
+    pub fn set_on_disconnect(&self, callback: impl Fn() + 'static) {
+        *self.on_disconnect.borrow_mut() = Some(std::rc::Rc::new(callback));
+    }
+
+    /// Claim the tune path, or `None` if another tune is still running.
+    /// The claim is released when the returned guard drops, including on an
+    /// early return or error.
+    pub fn try_begin_tune(&self) -> Option<TuneGuard> {
+        if self.tune_busy.replace(true) {
+            return None;
+        }
+        Some(TuneGuard(self.tune_busy.clone()))
+    }
+
+    /// Register the callback invoked whenever the log changes.
+    pub fn set_on_log(&self, callback: impl Fn() + 'static) {
+        *self.on_log.borrow_mut() = Some(std::rc::Rc::new(callback));
+    }
+
+    /// Turn recording of sent commands on or off. Received frames are
+    /// always logged.
+    pub fn set_log_tx(&self, enabled: bool) {
+        self.log_tx.set(enabled);
+    }
+
+    /// Allow or block commands that can key the transmitter.
+    pub fn set_tx_enabled(&self, enabled: bool) {
+        self.tx_enabled.set(enabled);
+    }
+
+    pub fn tx_enabled(&self) -> bool {
+        self.tx_enabled.get()
+    }
+
+    pub fn set_kenwood_model(&self, model: KenwoodModel) {
+        self.kenwood_model.set(model);
+    }
+
+    pub fn kenwood_model(&self) -> KenwoodModel {
+        self.kenwood_model.get()
+    }
+
+    pub fn session_stats(&self) -> SessionStats {
+        self.stats.get()
+    }
+
+    fn count(&self, update: impl FnOnce(&mut SessionStats)) {
+        let mut stats = self.stats.get();
+        update(&mut stats);
+        self.stats.set(stats);
+    }
+
+    /// Set the pause between sequenced commands. Older radios drop
+    /// commands that arrive too close together.
+    pub fn set_command_delay(&self, delay_ms: u32) {
+        self.command_delay_ms.set(delay_ms);
+    }
+
+    pub fn command_delay(&self) -> u32 {
+        self.command_delay_ms.get()
+    }
+
+    pub fn log_rx(&self, text: impl Into<String>) {
+        self.push_log(LogDirection::Rx, text.into());
+    }
+
+    pub fn log_entries(&self) -> Vec<LogEntry> {
+        self.log.borrow().clone()
+    }
+
+    pub fn clear_log(&self) {
+        self.log.borrow_mut().clear();
+        self.notify_log();
+    }
+
+    fn push_log(&self, direction: LogDirection, text: String) {
+        let now = js_sys::Date::new_0();
+        let time = format!(
+            "{:02}:{:02}:{:02}.{:03}",
+            now.get_hours(),
+            now.get_minutes(),
+            now.get_seconds(),
+            now.get_milliseconds()
+        );
+        {
+            let mut log = self.log.borrow_mut();
+            log.push(LogEntry { time, direction, text });
+            if log.len() > MAX_LOG_ENTRIES {
+                let excess = log.len() - MAX_LOG_ENTRIES;
+                log.drain(..excess);
+            }
+        }
+        self.notify_log();
+    }
+
+    fn notify_log(&self) {
+        let callback = self.on_log.borrow().clone();
+        if let Some(callback) = callback {
+            callback();
         }
     }
 
     pub async fn connect(&self, baud_rate: u32) -> Result<(), JsValue> {
+        let serial = Self::navigator_serial()?;
+        let request_port = Reflect::get(&serial, &JsValue::from_str("requestPort"))?
+            .dyn_into::<Function>()?;
+        let promise = request_port.call0(&serial)?;
+        let port_js = JsFuture::from(promise.dyn_into::<Promise>()?).await?;
+
+        self.open_port(port_js, baud_rate).await
+    }
+
+    /// Reopen the first port this origin has already been granted, without
+    /// prompting. Returns `Ok(false)` when no port is remembered.
+    pub async fn try_reconnect(&self, baud_rate: u32) -> Result<bool, JsValue> {
+        let serial = Self::navigator_serial()?;
+        let get_ports = Reflect::get(&serial, &JsValue::from_str("getPorts"))?
+            .dyn_into::<Function>()?;
+        let promise = get_ports.call0(&serial)?;
+        let ports = JsFuture::from(promise.dyn_into::<Promise>()?).await?;
+        let ports = ports.dyn_into::<js_sys::Array>()?;
+        if ports.length() == 0 {
+            return Ok(false);
+        }
+
+        self.open_port(ports.get(0), baud_rate).await?;
+        Ok(true)
+    }
+
+    /// Friendly name for the open port from its USB IDs, e.g.
+    /// "FTDI FT232R (0403:6001)". `None` when no port is open or the port
+    /// isn't USB.
+    pub fn port_label(&self) -> Option<String> {
+        let port = self.port.borrow().clone()?;
+        let get_info = Reflect::get(&port, &JsValue::from_str("getInfo"))
+            .ok()?
+            .dyn_into::<Function>()
+            .ok()?;
+        let info = get_info.call0(&port).ok()?;
+        let id = |key: &str| {
+            Reflect::get(&info, &JsValue::from_str(key))
+                .ok()?
+                .as_f64()
+                .map(|v| v as u16)
+        };
+        Some(usb_device_label(id("usbVendorId")?, id("usbProductId")?))
+    }
+
+    fn navigator_serial() -> Result<JsValue, JsValue> {
         let window: Window = web_sys::window().ok_or_else(|| JsValue::from_str("no window"))?;
         let navigator = window.navigator();
         let serial = Reflect::get(&navigator, &JsValue::from_str("serial"))?;
@@ -33,11 +353,10 @@ impl SerialManager {
                 "Web Serial not available. Use Chromium and HTTPS or localhost.",
             ));
         }
-        let request_port = Reflect::get(&serial, &JsValue::from_str("requestPort"))?
-            .dyn_into::<Function>()?;
-        let promise = request_port.call0(&serial)?;
-        let port_js = JsFuture::from(promise.dyn_into::<Promise>()?).await?;
+        Ok(serial)
+    }
 
+    async fn open_port(&self, port_js: JsValue, baud_rate: u32) -> Result<(), JsValue> {
         let options = Object::new();
         Reflect::set(&options, &JsValue::from_str("baudRate"), &JsValue::from_f64(baud_rate as f64))?;
 
@@ -47,10 +366,16 @@ impl SerialManager {
         JsFuture::from(open_promise.dyn_into::<Promise>()?).await?;
 
         *self.port.borrow_mut() = Some(port_js);
+        self.stats.set(SessionStats {
+            connected_at_ms: Some(js_sys::Date::now()),
+            ..SessionStats::default()
+        });
+        self.watch_for_unplug()?;
         // clear any existing reader when connecting
         {
             let mut guard = self.reader.lock().await;
             *guard = None;
+            self.pending_read.borrow_mut().take();
         }
         // clear buffer
         {
@@ -61,43 +386,266 @@ impl SerialManager {
         Ok(())
     }
 
+    /// Listen for `navigator.serial`'s `disconnect` event and, when it's for
+    /// our port, drop the port and reader state and notify `on_disconnect`.
+    fn watch_for_unplug(&self) -> Result<(), JsValue> {
+        let serial = Self::navigator_serial()?;
+        self.remove_unplug_listener();
+
+        let sm = self.clone();
+        let listener = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            let target = Reflect::get(&event, &JsValue::from_str("target")).unwrap_or(JsValue::NULL);
+            let ours = sm
+                .port
+                .borrow()
+                .as_ref()
+                .is_some_and(|port| Object::is(port, &target));
+            if !ours {
+                return;
+            }
+            web_sys::console::log_1(&JsValue::from_str("serial: port disconnected"));
+            *sm.port.borrow_mut() = None;
+            sm.drain_running.set(false);
+            let cleanup = sm.clone();
+            spawn_local(async move {
+                *cleanup.reader.lock().await = None;
+                cleanup.pending_read.borrow_mut().take();
+                cleanup.buffer.lock().await.clear();
+            });
+            let callback = sm.on_disconnect.borrow().clone();
+            if let Some(callback) = callback {
+                callback();
+            }
+        });
+        let add = Reflect::get(&serial, &JsValue::from_str("addEventListener"))?
+            .dyn_into::<Function>()?;
+        add.call2(&serial, &JsValue::from_str("disconnect"), listener.as_ref())?;
+        *self.disconnect_listener.borrow_mut() = Some(listener);
+        Ok(())
+    }
+
+    fn remove_unplug_listener(&self) {
+        let Some(listener) = self.disconnect_listener.borrow_mut().take() else {
+            return;
+        };
+        if let Ok(serial) = Self::navigator_serial() {
+            if let Ok(remove) = Reflect::get(&serial, &JsValue::from_str("removeEventListener"))
+                .and_then(|f| f.dyn_into::<Function>())
+            {
+                let _ = remove.call2(&serial, &JsValue::from_str("disconnect"), listener.as_ref());
+            }
+        }
+    }
+
+    /// Write `command` to the port. A write that fails while the port is
+    /// still open is retried once after a short pause, since a busy USB bus
+    /// occasionally rejects one; a missing or closed port fails at once.
+    /// Writes from concurrent tasks queue on `write_lock` rather than
+    /// racing for the port's writer.
     pub async fn write_command(&self, command: &str) -> Result<(), JsValue> {
+        if !self.tx_enabled.get() && may_transmit(command) {
+            return Err(JsValue::from_str(&format!(
+                "TX disabled: {:?} can key the radio; turn on TX first",
+                command.trim()
+            )));
+        }
+        let _writing = self.write_lock.lock().await;
+        let writable = self.writable()?;
+        if let Err(err) = Self::write_once(&writable, command).await {
+            web_sys::console::log_1(&JsValue::from_str(&format!(
+                "serial: write of {:?} failed, retrying: {:?}",
+                command, err
+            )));
+            TimeoutFuture::new(WRITE_RETRY_MS).await;
+            let retried = match self.writable() {
+                Ok(writable) => Self::write_once(&writable, command).await,
+                Err(err) => Err(err),
+            };
+            if let Err(err) = retried {
+                self.count(|stats| stats.errors += 1);
+                return Err(err);
+            }
+        }
+        self.count(|stats| stats.commands_sent += 1);
+        if self.log_tx.get() {
+            self.push_log(LogDirection::Tx, command.to_string());
+        }
+        Ok(())
+    }
+
+    /// The port's writable stream, or an error if there's nothing to write to.
+    fn writable(&self) -> Result<JsValue, JsValue> {
         let port = self
             .port
             .borrow()
             .as_ref()
             .ok_or_else(|| JsValue::from_str("serial not connected"))?
             .clone();
-
         let writable = Reflect::get(&port, &JsValue::from_str("writable"))?;
         if writable.is_undefined() || writable.is_null() {
             return Err(JsValue::from_str("port not writable"));
         }
+        Ok(writable)
+    }
 
-        let get_writer = Reflect::get(&writable, &JsValue::from_str("getWriter"))?
+    /// One write attempt. The writer lock is released whether or not the
+    /// write succeeded, so a retry can take it again.
+    async fn write_once(writable: &JsValue, command: &str) -> Result<(), JsValue> {
+        let get_writer = Reflect::get(writable, &JsValue::from_str("getWriter"))?
             .dyn_into::<Function>()?;
-        let writer = get_writer.call0(&writable)?;
+        let writer = get_writer.call0(writable)?;
 
-        let bytes = command.as_bytes();
-        let uint8 = Uint8Array::from(bytes);
-        let write_fn = Reflect::get(&writer, &JsValue::from_str("write"))?
-            .dyn_into::<Function>()?;
-        let write_promise = write_fn.call1(&writer, &uint8)?;
-        JsFuture::from(write_promise.dyn_into::<Promise>()?).await?;
+        let uint8 = Uint8Array::from(command.as_bytes());
+        let write = async {
+            let write_fn = Reflect::get(&writer, &JsValue::from_str("write"))?
+                .dyn_into::<Function>()?;
+            let write_promise = write_fn.call1(&writer, &uint8)?;
+            JsFuture::from(write_promise.dyn_into::<Promise>()?).await.map(|_| ())
+        };
+        let result = write.await;
 
         let release = Reflect::get(&writer, &JsValue::from_str("releaseLock"))?
             .dyn_into::<Function>()?;
         release.call0(&writer)?;
-        Ok(())
+        result
+    }
+
+    /// Take every queued unsolicited frame, i.e. ones no query was waiting
+    /// for, waiting briefly if none are queued yet. Bursts (e.g. `AI2;`
+    /// auto-information output) come back together. The reader itself is
+    /// owned by the pump and stays locked until `stop_reader()` or
+    /// `disconnect()`.
+    pub async fn read_all_frames(&self) -> Result<Vec<String>, JsValue> {
+        self.ensure_pump();
+        if self.unsolicited.borrow().is_empty() {
+            TimeoutFuture::new(FRAME_POLL_MS).await;
+        }
+        Ok(self.unsolicited.borrow_mut().drain(..).collect())
     }
 
-    /// Read a single response frame from the serial port's reader.
-    /// Returns decoded UTF-8 string or hex if non-UTF8.
-    /// Read a single chunk from a persistent reader (creating it if needed).
-    /// This does not release the reader lock; the reader remains owned until `disconnect()`.
-    pub async fn read_from_persistent_reader(&self) -> Result<String, JsValue> {
-        // Debugging logs to help trace reader lifecycle and incoming data
-        web_sys::console::log_1(&JsValue::from_str("serial: read_from_persistent_reader start"));
+    /// Wait up to `timeout_ms` for the next frame the radio sends, taking
+    /// one already queued as unsolicited first. The read goes through the
+    /// pump as a waiter that matches any prefix, so it never stops a reader
+    /// a concurrent `query` depends on; frames a query is waiting for still
+    /// go to that query.
+    pub async fn read_one_frame(&self, timeout_ms: u32) -> Result<Option<String>, JsValue> {
+        if self.port.borrow().is_none() {
+            return Err(JsValue::from_str("not connected"));
+        }
+        if let Some(frame) = self.unsolicited.borrow_mut().pop_front() {
+            return Ok(Some(frame));
+        }
+        let (reply, answer) = oneshot::channel();
+        self.waiters.borrow_mut().push(FrameWaiter { prefix: String::new(), reply });
+        self.ensure_pump();
+        match future::select(answer, TimeoutFuture::new(timeout_ms)).await {
+            Either::Left((Ok(frame), _)) => Ok(Some(frame)),
+            Either::Left((Err(_), _)) => Err(JsValue::from_str("reader stopped")),
+            Either::Right(_) => Ok(None),
+        }
+    }
+
+    /// Send `command` and wait up to `timeout_ms` for the answer starting
+    /// with `prefix`. The waiter is registered before the write, so a fast
+    /// reply can't slip past it into the unsolicited queue.
+    pub async fn query(&self, command: &str, prefix: &str, timeout_ms: u32) -> Result<String, JsValue> {
+        let (reply, answer) = oneshot::channel();
+        self.waiters.borrow_mut().push(FrameWaiter { prefix: prefix.to_string(), reply });
+        self.ensure_pump();
+        self.write_command(command).await?;
+        match future::select(answer, TimeoutFuture::new(timeout_ms)).await {
+            Either::Left((Ok(frame), _)) => Ok(frame),
+            Either::Left((Err(_), _)) => Err(JsValue::from_str("reader stopped")),
+            // Dropping `answer` cancels the waiter; the pump prunes it.
+            Either::Right(_) => {
+                self.count(|stats| stats.errors += 1);
+                Err(JsValue::from_str(&format!("no {} frame within {}ms", prefix, timeout_ms)))
+            }
+        }
+    }
+
+    /// Start the pump if it isn't already running.
+    fn ensure_pump(&self) {
+        if self.pump_running.get() || self.port.borrow().is_none() {
+            return;
+        }
+        self.pump_running.set(true);
+        let generation = self.pump_generation.get().wrapping_add(1);
+        self.pump_generation.set(generation);
+        let sm = self.clone();
+        spawn_local(async move {
+            while sm.pump_generation.get() == generation && sm.port.borrow().is_some() {
+                match sm.read_chunk().await {
+                    Ok(true) => {
+                        let frames = split_frames(&mut *sm.buffer.lock().await);
+                        for frame in frames {
+                            sm.route_frame(frame);
+                        }
+                        if sm.pump_idle() {
+                            // Give up the pump before releasing, so a query
+                            // arriving meanwhile starts a fresh one.
+                            sm.pump_running.set(false);
+                            sm.pump_generation.set(generation.wrapping_add(1));
+                            let reader = sm.reader.lock().await.take();
+                            if let Some(reader) = reader {
+                                let _ = sm.release_reader(reader).await;
+                            }
+                            return;
+                        }
+                    }
+                    Ok(false) => break,
+                    Err(_) => {
+                        sm.count(|stats| stats.errors += 1);
+                        break;
+                    }
+                }
+            }
+            if sm.pump_generation.get() == generation {
+                sm.pump_running.set(false);
+            }
+        });
+    }
+
+    /// Whether nothing needs the pump: no query is waiting and the read
+    /// loop's drain is off.
+    fn pump_idle(&self) -> bool {
+        let mut waiters = self.waiters.borrow_mut();
+        waiters.retain(|w| !w.reply.is_canceled());
+        waiters.is_empty() && !self.drain_running.get()
+    }
+
+    /// Stop the pump and fail any waiting queries.
+    fn stop_pump(&self) {
+        self.pump_running.set(false);
+        self.pump_generation.set(self.pump_generation.get().wrapping_add(1));
+        self.waiters.borrow_mut().clear();
+        self.unsolicited.borrow_mut().clear();
+    }
+
+    fn route_frame(&self, frame: String) {
+        self.count(|stats| stats.frames_received += 1);
+        let mut waiters = self.waiters.borrow_mut();
+        waiters.retain(|w| !w.reply.is_canceled());
+        if let Some(pos) = waiter_for(&waiters, &frame) {
+            let waiter = waiters.remove(pos);
+            // A receiver dropped since the prune above just loses the frame.
+            let _ = waiter.reply.send(frame);
+            return;
+        }
+        drop(waiters);
+        let mut unsolicited = self.unsolicited.borrow_mut();
+        unsolicited.push_back(frame);
+        if unsolicited.len() > MAX_UNSOLICITED_FRAMES {
+            unsolicited.pop_front();
+        }
+    }
+
+    /// Read a single chunk from a persistent reader (creating it if needed)
+    /// and append its raw bytes to the frame buffer. Returns `false` once
+    /// the stream is done, e.g. after the reader is cancelled. A read still
+    /// waiting after `READ_IDLE_MS` counts as no data: it is kept in
+    /// `pending_read` and the call returns `true` with nothing appended.
+    async fn read_chunk(&self) -> Result<bool, JsValue> {
         let port = self
             .port
             .borrow()
@@ -114,62 +662,73 @@ impl SerialManager {
         let reader = {
             let mut guard = self.reader.lock().await;
             if let Some(r) = guard.as_ref() {
-                web_sys::console::log_1(&JsValue::from_str("serial: reusing existing reader"));
                 r.clone()
             } else {
                 web_sys::console::log_1(&JsValue::from_str("serial: creating reader"));
                 let get_reader = Reflect::get(&readable, &JsValue::from_str("getReader"))?
                     .dyn_into::<Function>()?;
-                let r = get_reader.call0(&readable)?;
+                let r = match get_reader.call0(&readable) {
+                    Ok(r) => r,
+                    // A reader that was never released still holds the
+                    // stream. Release it if we still have it and try once more.
+                    Err(_) if Self::is_locked(&readable) => {
+                        web_sys::console::warn_1(&JsValue::from_str("serial: stream locked, releasing stale reader"));
+                        let stale = self.stale_reader.borrow_mut().take();
+                        if let Some(stale) = stale {
+                            let _ = self.release_reader(stale).await;
+                        }
+                        get_reader.call0(&readable).map_err(|_| {
+                            JsValue::from_str("serial stream is locked by a stale reader; disconnect and reconnect")
+                        })?
+                    }
+                    Err(err) => return Err(err),
+                };
                 *guard = Some(r.clone());
                 r
             }
         };
 
-        let read_fn = Reflect::get(&reader, &JsValue::from_str("read"))?
-            .dyn_into::<Function>()?;
-        let read_promise = read_fn.call0(&reader)?;
-        let read_res = JsFuture::from(read_promise.dyn_into::<Promise>()?).await?;
+        let pending = self.pending_read.borrow_mut().take();
+        let read_promise = match pending {
+            Some(promise) => promise,
+            None => {
+                let read_fn = Reflect::get(&reader, &JsValue::from_str("read"))?
+                    .dyn_into::<Function>()?;
+                read_fn.call0(&reader)?.dyn_into::<Promise>()?
+            }
+        };
+        let read = JsFuture::from(read_promise.clone());
+        let read_res = match future::select(read, TimeoutFuture::new(READ_IDLE_MS)).await {
+            Either::Left((res, _)) => res?,
+            Either::Right(_) => {
+                *self.pending_read.borrow_mut() = Some(read_promise);
+                return Ok(true);
+            }
+        };
 
         let done = Reflect::get(&read_res, &JsValue::from_str("done"))?
             .as_bool()
             .unwrap_or(false);
-        let mut result = String::new();
         if !done {
             let val = Reflect::get(&read_res, &JsValue::from_str("value"))?;
-            let uint8 = Uint8Array::new(&val);
+            let bytes = Uint8Array::new(&val).to_vec();
             // Log the number of bytes received
-            let len = uint8.length();
-            web_sys::console::log_1(&JsValue::from_str(&format!("serial: read {} bytes", len)));
-            let vec = uint8.to_vec();
-            // Try UTF-8, else hex
-            match std::str::from_utf8(&vec) {
-                Ok(s) => result.push_str(s),
-                Err(_) => {
-                    let hex = vec.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ");
-                    result.push_str(&hex);
-                }
-            }
-            // Log the decoded payload for easier debugging in console
-            web_sys::console::log_1(&JsValue::from_str(&format!("serial: payload: {}", result)));
-        }
-
-        // Accumulate into buffer and return a complete frame (ending with ';') if available.
-        if !result.is_empty() {
-            let mut buf = self.buffer.lock().await;
-            buf.push_str(&result);
-            if let Some(pos) = buf.find(';') {
-                // include delimiter
-                let frame = buf.drain(..=pos).collect::<String>();
-                return Ok(frame);
-            }
+            web_sys::console::log_1(&JsValue::from_str(&format!("serial: read {} bytes", bytes.len())));
+            // Accumulate into buffer; the pump splits off complete frames (ending with ';').
+            self.buffer.lock().await.extend_from_slice(&bytes);
         }
 
-        Ok(String::new())
+        Ok(!done)
+    }
+
+    fn is_locked(stream: &JsValue) -> bool {
+        Reflect::get(stream, &JsValue::from_str("locked"))
+            .ok()
+            .and_then(|locked| locked.as_bool())
+            .unwrap_or(false)
     }
 
     /// Disconnect the serial port and cancel any active reader.
-    #[allow(dead_code)]
     pub async fn disconnect(&self) -> Result<(), JsValue> {
         // Take and cancel the reader under the mutex so we avoid RefCell panics.
         let reader_opt = {
@@ -177,20 +736,14 @@ impl SerialManager {
             guard.take()
         };
         if let Some(reader) = reader_opt {
-            let cancel = Reflect::get(&reader, &JsValue::from_str("cancel"))?;
-            if !cancel.is_undefined() && !cancel.is_null() {
-                let cancel_fn = cancel.dyn_into::<Function>()?;
-                let _ = JsFuture::from(cancel_fn.call0(&reader)?.dyn_into::<Promise>()?).await;
-            }
-            // try releaseLock as well
-            let release = Reflect::get(&reader, &JsValue::from_str("releaseLock"))?;
-            if !release.is_undefined() && !release.is_null() {
-                let release_fn = release.dyn_into::<Function>()?;
-                let _ = release_fn.call0(&reader);
-            }
+            self.release_reader(reader).await?;
         }
 
         // reader_claim removed; reader state is managed by the async Mutex
+        self.stop_pump();
+
+        // an intentional disconnect shouldn't be reported as an unplug
+        self.remove_unplug_listener();
 
         // Close port if present. Clone its JsValue out of the RefCell so we
         // don't hold a borrow across the `await` below.
@@ -204,6 +757,35 @@ impl SerialManager {
             *self.port.borrow_mut() = None;
         }
 
+        // drop any partial frame left from the old port
+        {
+            let mut b = self.buffer.lock().await;
+            b.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Cancel `reader` and release its lock on the stream. The reader is
+    /// parked in `stale_reader` until the release succeeds, so a failure
+    /// part-way through can still be recovered by `read_chunk`.
+    async fn release_reader(&self, reader: JsValue) -> Result<(), JsValue> {
+        // Any outstanding read belongs to this reader and ends with it.
+        self.pending_read.borrow_mut().take();
+        *self.stale_reader.borrow_mut() = Some(reader.clone());
+        let cancel = Reflect::get(&reader, &JsValue::from_str("cancel"))?;
+        if !cancel.is_undefined() && !cancel.is_null() {
+            let cancel_fn = cancel.dyn_into::<Function>()?;
+            let _ = JsFuture::from(cancel_fn.call0(&reader)?.dyn_into::<Promise>()?).await;
+        }
+        // try releaseLock as well
+        let release = Reflect::get(&reader, &JsValue::from_str("releaseLock"))?;
+        if !release.is_undefined() && !release.is_null() {
+            let release_fn = release.dyn_into::<Function>()?;
+            if release_fn.call0(&reader).is_ok() {
+                self.stale_reader.borrow_mut().take();
+            }
+        }
         Ok(())
     }
 
@@ -215,18 +797,9 @@ impl SerialManager {
             guard.take()
         };
         if let Some(reader) = reader_opt {
-            let cancel = Reflect::get(&reader, &JsValue::from_str("cancel"))?;
-            if !cancel.is_undefined() && !cancel.is_null() {
-                let cancel_fn = cancel.dyn_into::<Function>()?;
-                let _ = JsFuture::from(cancel_fn.call0(&reader)?.dyn_into::<Promise>()?).await;
-            }
-            // try releaseLock as well
-            let release = Reflect::get(&reader, &JsValue::from_str("releaseLock"))?;
-            if !release.is_undefined() && !release.is_null() {
-                let release_fn = release.dyn_into::<Function>()?;
-                let _ = release_fn.call0(&reader);
-            }
+            self.release_reader(reader).await?;
         }
+        self.stop_pump();
         // clear buffer when stopping reader
         {
             let mut b = self.buffer.lock().await;
@@ -235,27 +808,35 @@ impl SerialManager {
         Ok(())
     }
 
-    /// Spawn a background task that periodically reads from the persistent
-    /// reader to keep the internal buffer drained. Safe to call multiple
-    /// times; only one drain task runs at once.
+    /// Spawn a background task that keeps the pump reading, restarting it
+    /// after a read error, so the port's buffer never backs up while the
+    /// settings panel is open. Safe to call multiple times; only one drain
+    /// task runs at once.
     pub fn spawn_buffer_drain(&self) {
         if self.drain_running.get() {
             return;
         }
         self.drain_running.set(true);
+        // A stop followed quickly by a restart bumps the generation, so the
+        // old task exits on its next pass instead of running alongside.
+        let generation = self.drain_generation.get().wrapping_add(1);
+        self.drain_generation.set(generation);
         let sm = self.clone();
         spawn_local(async move {
-            while sm.drain_running.get() && sm.port.borrow().is_some() {
-                let _ = sm.read_from_persistent_reader().await;
+            while sm.drain_generation.get() == generation && sm.port.borrow().is_some() {
+                sm.ensure_pump();
                 TimeoutFuture::new(100).await;
             }
-            sm.drain_running.set(false);
+            if sm.drain_generation.get() == generation {
+                sm.drain_running.set(false);
+            }
         });
     }
 
     /// Stop the background drain task (if running).
     pub fn stop_buffer_drain(&self) {
         self.drain_running.set(false);
+        self.drain_generation.set(self.drain_generation.get().wrapping_add(1));
     }
 
     /// Send raw bytes (alias for write_command)
@@ -264,30 +845,390 @@ impl SerialManager {
     }
 
     pub async fn tune_kenwood_ts570(&self, freq_hz: u64, mode: &str) -> Result<(), JsValue> {
-        let mode_cmd = match mode.to_uppercase().as_str() {
-            "LSB" => "MD1;",
-            "USB" => "MD2;",
-            "CW" => "MD3;",
-            "FM" => "MD4;",
-            "AM" => "MD5;",
-            "SSB" => "MD2;",
-            "FT8" | "FT4" | "PSK31" | "RTTY" => "MD2;",
-            _ => "MD2;",
-        };
-
-        let freq_cmd = format!("FA{:011};", freq_hz);
+        let freq_cmd = frequency_command(freq_hz);
 
         // Ensure VFO A is active for RX/TX
         self.write_command("FR0;").await?;
         self.write_command("FT0;").await?;
-        TimeoutFuture::new(80).await;
+        TimeoutFuture::new(self.command_delay()).await;
 
         // Set frequency first, then mode, with short delays
         self.write_command(&freq_cmd).await?;
-        TimeoutFuture::new(80).await;
-        self.write_command(mode_cmd).await?;
+        TimeoutFuture::new(self.command_delay()).await;
+        self.write_kenwood_mode(mode).await
+    }
+
+    /// Send the mode commands for the selected Kenwood model.
+    async fn write_kenwood_mode(&self, mode: &str) -> Result<(), JsValue> {
+        for (i, command) in self.kenwood_model().mode_commands(mode).into_iter().enumerate() {
+            if i > 0 {
+                TimeoutFuture::new(self.command_delay()).await;
+            }
+            self.write_command(command).await?;
+        }
+        Ok(())
+    }
+
+    /// Send each command in order with the usual pause between them.
+    pub async fn send_sequence(&self, commands: &[String]) -> Result<(), JsValue> {
+        for (i, command) in commands.iter().enumerate() {
+            if i > 0 {
+                TimeoutFuture::new(self.command_delay()).await;
+            }
+            self.write_command(command).await?;
+        }
         Ok(())
     }
+
+    /// Tune for a split QSO: receive on VFO A at `rx_hz` and transmit on
+    /// VFO B at `tx_hz`, both in `mode`.
+    pub async fn tune_kenwood_ts570_split(&self, rx_hz: u64, tx_hz: u64, mode: &str) -> Result<(), JsValue> {
+        self.tune_kenwood_ts570(rx_hz, mode).await?;
+        TimeoutFuture::new(self.command_delay()).await;
+
+        // Select VFO B to set its frequency and mode, then return RX to A
+        // with TX left on B.
+        self.write_command("FR1;").await?;
+        self.write_command("FT1;").await?;
+        TimeoutFuture::new(self.command_delay()).await;
+        self.write_command(&vfo_b_frequency_command(tx_hz)).await?;
+        TimeoutFuture::new(self.command_delay()).await;
+        self.write_kenwood_mode(mode).await?;
+        TimeoutFuture::new(self.command_delay()).await;
+        self.write_command("FR0;").await?;
+        self.write_command("FT1;").await?;
+        Ok(())
+    }
+}
+
+/// RIT/XIT moves in fixed steps on the TS-570; `RU;`/`RD;` each move one.
+pub const RIT_STEP_HZ: i32 = 10;
+/// The clarifier's range either side of the dial frequency.
+pub const RIT_MAX_HZ: i32 = 9_990;
+
+/// Commands that move the clarifier from `from_hz` to `to_hz`: one `RU;`
+/// or `RD;` pulse per step of the difference, after clamping both to the
+/// radio's range.
+pub fn rit_commands(from_hz: i32, to_hz: i32) -> Vec<&'static str> {
+    let delta = to_hz.clamp(-RIT_MAX_HZ, RIT_MAX_HZ) - from_hz.clamp(-RIT_MAX_HZ, RIT_MAX_HZ);
+    let pulses = (delta.abs() + RIT_STEP_HZ / 2) / RIT_STEP_HZ;
+    let command = if delta >= 0 { "RU;" } else { "RD;" };
+    vec![command; pulses as usize]
+}
+
+/// USB-serial adapters commonly found in CAT cables, by vendor and product
+/// ID. A `None` product matches any device from that vendor.
+const USB_SERIAL_CHIPS: &[(u16, Option<u16>, &str)] = &[
+    (0x0403, Some(0x6001), "FTDI FT232R"),
+    (0x0403, Some(0x6015), "FTDI FT231X"),
+    (0x0403, None, "FTDI"),
+    (0x10c4, Some(0xea60), "Silicon Labs CP210x"),
+    (0x10c4, None, "Silicon Labs"),
+    (0x067b, Some(0x2303), "Prolific PL2303"),
+    (0x067b, None, "Prolific"),
+    (0x1a86, Some(0x7523), "WCH CH340"),
+];
+
+/// Describe a USB device, naming the chipset when it's a known one.
+fn usb_device_label(vendor: u16, product: u16) -> String {
+    let ids = format!("{:04x}:{:04x}", vendor, product);
+    USB_SERIAL_CHIPS
+        .iter()
+        .find(|(v, p, _)| *v == vendor && p.is_none_or(|p| p == product))
+        .map(|(_, _, name)| format!("{} ({})", name, ids))
+        .unwrap_or_else(|| format!("USB {}", ids))
+}
+
+/// DSP widths the TS-570 offers for `FW` in CW and FSK, in Hz.
+const CW_FILTER_WIDTHS_HZ: &[u32] = &[50, 80, 100, 150, 200, 300, 400, 500, 600, 1000, 2000];
+/// SSB low-cut (`SL`) and high-cut (`SH`) frequencies, indexed by their
+/// two-digit command code.
+const SSB_LOW_CUTS_HZ: &[u32] = &[10, 50, 100, 200, 300, 400, 500, 600, 700, 800, 900, 1000];
+const SSB_HIGH_CUTS_HZ: &[u32] = &[1400, 1600, 1800, 2000, 2200, 2400, 2600, 2800, 3000, 3400, 4000, 5000];
+/// Low cut used for SSB widths, a common setting that keeps low-end hum
+/// and rumble out.
+const SSB_LOW_CUT_CODE: usize = 3;
+
+/// Index of the entry in `values` closest to `target`.
+fn nearest_index(values: &[u32], target: u32) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, &v)| v.abs_diff(target))
+        .map_or(0, |(i, _)| i)
+}
+
+/// Commands setting the receive bandwidth closest to `width_hz` in the
+/// given TS-570 mode, along with the width actually applied. CW and FSK
+/// use the DSP width (`FW`); SSB fixes the low cut and moves the high cut
+/// (`SL`/`SH`). AM and FM have no adjustable width here.
+pub fn filter_width_commands(mode_code: u8, width_hz: u32) -> Option<(Vec<String>, u32)> {
+    match mode_code {
+        3 | 6 | 7 | 9 => {
+            let width = CW_FILTER_WIDTHS_HZ[nearest_index(CW_FILTER_WIDTHS_HZ, width_hz)];
+            Some((vec![format!("FW{:04};", width)], width))
+        }
+        1 | 2 => {
+            let low = SSB_LOW_CUTS_HZ[SSB_LOW_CUT_CODE];
+            let high_code = nearest_index(SSB_HIGH_CUTS_HZ, width_hz + low);
+            Some((
+                vec![format!("SL{:02};", SSB_LOW_CUT_CODE), format!("SH{:02};", high_code)],
+                SSB_HIGH_CUTS_HZ[high_code] - low,
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Whether `command` (possibly several `;`-terminated commands) can key the
+/// transmitter: `TX`, `KY` with text to send, or `AC` starting a tuner cycle.
+pub fn may_transmit(command: &str) -> bool {
+    command
+        .split(';')
+        .map(|c| c.trim().to_ascii_uppercase())
+        .any(|c| {
+            c.starts_with("TX")
+                || (c.starts_with("KY") && c.len() > 2)
+                || (c.starts_with("AC") && c.as_bytes().get(4) == Some(&b'1'))
+        })
+}
+
+/// Check a typed raw command before it goes to the radio: it must be
+/// non-empty printable ASCII, and a missing trailing `;` is added so the
+/// radio isn't left waiting for the rest of the command.
+pub fn normalize_raw_command(input: &str) -> Result<String, String> {
+    let command = input.trim();
+    if command.is_empty() {
+        return Err("Empty raw command".to_string());
+    }
+    if !command.bytes().all(|b| b.is_ascii_graphic() || b == b' ') {
+        return Err("Raw command must be printable ASCII".to_string());
+    }
+    if command.ends_with(';') {
+        Ok(command.to_string())
+    } else {
+        Ok(format!("{};", command))
+    }
+}
+
+/// Split a macro such as `FA00014062000;MD3;FW0200;` into individual
+/// commands, each re-terminated with `;`. Every command must be a two-letter
+/// CAT mnemonic followed by optional alphanumeric parameters.
+pub fn parse_cat_sequence(input: &str) -> Result<Vec<String>, String> {
+    let commands: Vec<String> = input
+        .split(';')
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(|c| {
+            let upper = c.to_ascii_uppercase();
+            let valid = upper.len() >= 2
+                && upper.bytes().take(2).all(|b| b.is_ascii_alphabetic())
+                && upper.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'-');
+            if valid {
+                Ok(format!("{};", upper))
+            } else {
+                Err(format!("Not a CAT command: {}", c))
+            }
+        })
+        .collect::<Result<_, _>>()?;
+    if commands.is_empty() {
+        return Err("Empty command sequence".to_string());
+    }
+    Ok(commands)
+}
+
+/// Remove every complete `;`-terminated frame from `buf` and decode it,
+/// trimming stray whitespace between frames. A trailing partial frame is
+/// left in place for the next read. Frames that aren't valid UTF-8 are
+/// shown as hex.
+fn split_frames(buf: &mut Vec<u8>) -> Vec<String> {
+    let Some(last) = buf.iter().rposition(|&b| b == b';') else {
+        return Vec::new();
+    };
+    let complete: Vec<u8> = buf.drain(..=last).collect();
+    complete
+        .split_inclusive(|&b| b == b';')
+        .map(|frame| match std::str::from_utf8(frame) {
+            Ok(text) => text.trim_start().to_string(),
+            Err(_) => frame.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" "),
+        })
+        .filter(|frame| frame != ";")
+        .collect()
+}
+
+/// TS-570 command setting VFO A to `freq_hz`, e.g. `FA00014062000;`.
+pub fn frequency_command(freq_hz: u64) -> String {
+    format!("FA{:011};", freq_hz)
+}
+
+/// TS-570 command setting VFO B to `freq_hz`.
+pub fn vfo_b_frequency_command(freq_hz: u64) -> String {
+    format!("FB{:011};", freq_hz)
+}
+
+/// Map a spot mode onto the TS-570 `MD` command. Digital modes ride on USB.
+pub fn mode_command(mode: &str) -> &'static str {
+    match mode.to_uppercase().as_str() {
+        "LSB" => "MD1;",
+        "USB" => "MD2;",
+        "CW" => "MD3;",
+        "FM" => "MD4;",
+        "AM" => "MD5;",
+        "SSB" => "MD2;",
+        "FT8" | "FT4" | "PSK31" | "RTTY" => "MD2;",
+        _ => "MD2;",
+    }
+}
+
+/// Whether a spot mode is a soundcard data mode rather than voice or CW.
+fn is_data_mode(mode: &str) -> bool {
+    matches!(mode.to_uppercase().as_str(), "FT8" | "FT4" | "PSK31" | "RTTY" | "DATA")
+}
+
+/// Kenwood models whose CAT dialect differs from the TS-570 the driver was
+/// written against. Where they differ:
+///
+/// - `MD`: codes 1–9 mean the same on all three, but the TS-590 and TS-890
+///   select data input with a separate `DA` command. Data spots get
+///   `MD2;DA1;` (USB-DATA) there and voice modes `DA0;`; the TS-570 has no
+///   `DA` and sends data modes as plain USB.
+/// - `GC`: the TS-570 uses 1 = fast and 2 = slow; the TS-590 and TS-890
+///   swap them.
+/// - `SM`: the TS-590 and TS-890 echo the meter selector (`SM0nnnn;`),
+///   which `parse_smeter` already accepts.
+///
+/// `FA`/`FB` (11 digits), `FR`/`FT`, `KS`, `NB`, `NR`, `PC` and `ID` are
+/// the same on all three.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum KenwoodModel {
+    #[default]
+    Ts570,
+    Ts590,
+    Ts890,
+}
+
+impl KenwoodModel {
+    pub const ALL: [KenwoodModel; 3] = [KenwoodModel::Ts570, KenwoodModel::Ts590, KenwoodModel::Ts890];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            KenwoodModel::Ts570 => "ts570",
+            KenwoodModel::Ts590 => "ts590",
+            KenwoodModel::Ts890 => "ts890",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            KenwoodModel::Ts570 => "TS-570",
+            KenwoodModel::Ts590 => "TS-590S/SG",
+            KenwoodModel::Ts890 => "TS-890S",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.key() == key)
+    }
+
+    fn has_data_switch(self) -> bool {
+        self != KenwoodModel::Ts570
+    }
+
+    /// Commands that put the radio in `mode`, in order.
+    pub fn mode_commands(self, mode: &str) -> Vec<&'static str> {
+        let md = mode_command(mode);
+        if !self.has_data_switch() || md == "MD3;" {
+            return vec![md];
+        }
+        vec![md, if is_data_mode(mode) { "DA1;" } else { "DA0;" }]
+    }
+
+    /// The `GC` code for `agc` on this model.
+    pub fn agc_code(self, agc: AgcSpeed) -> u32 {
+        let fast_first = self == KenwoodModel::Ts570;
+        match agc {
+            AgcSpeed::Off => 0,
+            AgcSpeed::Fast => if fast_first { 1 } else { 2 },
+            AgcSpeed::Slow => if fast_first { 2 } else { 1 },
+        }
+    }
+
+    /// The AGC speed for a `GC` code, or `None` for a value this model
+    /// doesn't use.
+    pub fn agc_from_code(self, code: u32) -> Option<AgcSpeed> {
+        AgcSpeed::ALL.into_iter().find(|agc| self.agc_code(*agc) == code)
+    }
+}
+
+/// Common CAT operations, implemented by each radio driver so the app can
+/// pick one at runtime. Methods return boxed futures to keep the trait
+/// usable as `dyn RigDriver`; all drivers share the same `SerialManager`.
+pub trait RigDriver {
+    fn tune<'a>(&'a self, serial: &'a SerialManager, freq_hz: u64, mode: &'a str) -> LocalBoxFuture<'a, Result<(), JsValue>>;
+    fn set_mode<'a>(&'a self, serial: &'a SerialManager, mode: &'a str) -> LocalBoxFuture<'a, Result<(), JsValue>>;
+    fn set_vfo_a<'a>(&'a self, serial: &'a SerialManager) -> LocalBoxFuture<'a, Result<(), JsValue>>;
+    fn set_vfo_b<'a>(&'a self, serial: &'a SerialManager) -> LocalBoxFuture<'a, Result<(), JsValue>>;
+    fn query_frequency<'a>(&'a self, serial: &'a SerialManager) -> LocalBoxFuture<'a, Result<String, JsValue>>;
+    fn send_raw<'a>(&'a self, serial: &'a SerialManager, cmd: &'a str) -> LocalBoxFuture<'a, Result<(), JsValue>>;
+    /// Parse this radio's `FA`/`FB` answer into Hz.
+    fn parse_frequency(&self, frame: &str) -> Option<u64>;
+}
+
+/// AGC time constant as carried by the `GC` command; the code for each
+/// depends on the model, see `KenwoodModel::agc_code`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AgcSpeed {
+    Off,
+    Fast,
+    Slow,
+}
+
+impl AgcSpeed {
+    pub const ALL: [AgcSpeed; 3] = [AgcSpeed::Fast, AgcSpeed::Slow, AgcSpeed::Off];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AgcSpeed::Off => "Off",
+            AgcSpeed::Fast => "Fast",
+            AgcSpeed::Slow => "Slow",
+        }
+    }
+}
+
+/// The radio's VFO configuration as read back by `query_split`. Fields are
+/// `None` when the radio didn't answer or answered something unexpected.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SplitState {
+    pub vfo_a_hz: Option<u64>,
+    pub vfo_b_hz: Option<u64>,
+    /// Receive VFO from `FR;`: 0 = A, 1 = B.
+    pub rx_vfo: Option<u8>,
+    /// Transmit VFO from `FT;`: 0 = A, 1 = B.
+    pub tx_vfo: Option<u8>,
+}
+
+impl SplitState {
+    /// Whether the radio transmits on a different VFO than it receives on,
+    /// or `None` if it didn't report both.
+    pub fn is_split(&self) -> Option<bool> {
+        Some(self.rx_vfo? != self.tx_vfo?)
+    }
+
+    pub fn rx_hz(&self) -> Option<u64> {
+        self.vfo_hz(self.rx_vfo?)
+    }
+
+    pub fn tx_hz(&self) -> Option<u64> {
+        self.vfo_hz(self.tx_vfo?)
+    }
+
+    fn vfo_hz(&self, vfo: u8) -> Option<u64> {
+        match vfo {
+            0 => self.vfo_a_hz,
+            1 => self.vfo_b_hz,
+            _ => None,
+        }
+    }
 }
 
 /// Lightweight helper for Kenwood-style commands. Kept separate so we can
@@ -295,7 +1236,6 @@ impl SerialManager {
 pub struct KenwoodDriver;
 
 impl KenwoodDriver {
-    #[allow(dead_code)]
     pub async fn tune(serial: &SerialManager, freq_hz: u64, mode: &str) -> Result<(), JsValue> {
         serial.tune_kenwood_ts570(freq_hz, mode).await
     }
@@ -323,23 +1263,676 @@ impl KenwoodDriver {
     }
 
     pub async fn set_mode(serial: &SerialManager, mode: &str) -> Result<(), JsValue> {
-        let mode_cmd = match mode.to_uppercase().as_str() {
-            "LSB" => "MD1;",
-            "USB" => "MD2;",
-            "CW" => "MD3;",
-            "FM" => "MD4;",
-            "AM" => "MD5;",
-            _ => "MD2;",
-        };
-        serial.write_command(mode_cmd).await
+        serial.write_kenwood_mode(mode).await
+    }
+
+    pub async fn query_frequency(serial: &SerialManager) -> Result<String, JsValue> {
+        Self::query_frequency_timeout(serial, DEFAULT_FRAME_TIMEOUT_MS).await
+    }
+
+    /// Query VFO A and wait up to `timeout_ms` for the `FA` answer; a slow
+    /// radio otherwise hands back an empty read.
+    pub async fn query_frequency_timeout(serial: &SerialManager, timeout_ms: u32) -> Result<String, JsValue> {
+        serial.query("FA;", "FA", timeout_ms).await
+    }
+
+    /// Read both VFOs and the RX/TX VFO selection. Any part the radio
+    /// doesn't answer is left as `None` rather than failing the whole query.
+    pub async fn query_split(serial: &SerialManager) -> Result<SplitState, JsValue> {
+        let mut state = SplitState::default();
+        for prefix in ["FA", "FB", "FR", "FT"] {
+            let Ok(frame) = serial.query(&format!("{};", prefix), prefix, DEFAULT_FRAME_TIMEOUT_MS).await else {
+                continue;
+            };
+            match prefix {
+                "FA" => state.vfo_a_hz = Self::parse_frequency(&frame),
+                "FB" => state.vfo_b_hz = Self::parse_frequency(&frame),
+                "FR" => state.rx_vfo = Self::parse_vfo_select(&frame, "FR"),
+                _ => state.tx_vfo = Self::parse_vfo_select(&frame, "FT"),
+            }
+        }
+        Ok(state)
+    }
+
+    /// Parse an `FR`/`FT` VFO selection frame such as `FR1;`.
+    pub fn parse_vfo_select(frame: &str, prefix: &str) -> Option<u8> {
+        let frame = frame.trim();
+        let body = frame.strip_suffix(';').unwrap_or(frame);
+        match body.strip_prefix(prefix)? {
+            "0" => Some(0),
+            "1" => Some(1),
+            _ => None,
+        }
+    }
+
+    pub async fn query_mode(serial: &SerialManager) -> Result<String, JsValue> {
+        serial.query("MD;", "MD", DEFAULT_FRAME_TIMEOUT_MS).await
+    }
+
+    /// Human label for a TS-570 `MD` code.
+    pub fn mode_label(code: u8) -> Option<&'static str> {
+        match code {
+            1 => Some("LSB"),
+            2 => Some("USB"),
+            3 => Some("CW"),
+            4 => Some("FM"),
+            5 => Some("AM"),
+            6 => Some("FSK"),
+            7 => Some("CW-R"),
+            9 => Some("FSK-R"),
+            _ => None,
+        }
+    }
+
+    /// Parse an `MD` mode frame such as `MD3;` into its mode code.
+    pub fn parse_mode(frame: &str) -> Option<u8> {
+        let frame = frame.trim();
+        let body = frame.strip_suffix(';').unwrap_or(frame);
+        let digit = body.strip_prefix("MD")?;
+        if digit.len() != 1 {
+            return None;
+        }
+        digit.parse::<u8>().ok()
+    }
+
+    /// The mode code `tune` would send for a spot mode.
+    pub fn expected_mode_code(mode: &str) -> Option<u8> {
+        Self::parse_mode(mode_command(mode))
+    }
+
+    pub async fn query_smeter(serial: &SerialManager) -> Result<String, JsValue> {
+        serial.query("SM0;", "SM", DEFAULT_FRAME_TIMEOUT_MS).await
+    }
+
+    /// Parse an S-meter frame into its raw level. The TS-570 answers
+    /// `SMnnnn;`; later models echo the meter selector as `SM0nnnn;`.
+    pub fn parse_smeter(frame: &str) -> Option<u16> {
+        let frame = frame.trim();
+        let body = frame.strip_suffix(';').unwrap_or(frame);
+        let digits = body.strip_prefix("SM")?;
+        if !(digits.len() == 4 || digits.len() == 5) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        digits[digits.len() - 4..].parse::<u16>().ok()
+    }
+
+    /// Interpret a TS-570 meter level (0000–0030). The first half of the
+    /// scale covers S0–S9, the rest S9+ in roughly 4 dB steps up to +60 dB.
+    pub fn smeter_label(raw: u16) -> String {
+        let raw = raw.min(30);
+        if raw <= 15 {
+            format!("S{}", raw * 9 / 15)
+        } else {
+            format!("S9+{}dB", (raw - 15) * 4)
+        }
+    }
+
+    /// Set RF output power. The TS-570 accepts 5–100 W; requests outside
+    /// that range are clamped.
+    pub async fn set_power(serial: &SerialManager, watts: u32) -> Result<u32, JsValue> {
+        let watts = watts.clamp(5, 100);
+        serial.write_command(&format!("PC{:03};", watts)).await?;
+        Ok(watts)
+    }
+
+    /// Set the internal keyer speed; values outside the TS-570's range are
+    /// clamped. Returns the speed sent.
+    pub async fn set_keyer_speed(serial: &SerialManager, wpm: u32) -> Result<u32, JsValue> {
+        let wpm = wpm.clamp(KEYER_MIN_WPM, KEYER_MAX_WPM);
+        serial.write_command(&format!("KS{:03};", wpm)).await?;
+        Ok(wpm)
+    }
+
+    pub async fn query_keyer_speed(serial: &SerialManager) -> Result<String, JsValue> {
+        serial.query("KS;", "KS", DEFAULT_FRAME_TIMEOUT_MS).await
+    }
+
+    /// Parse a `KSnnn;` keyer speed frame into WPM.
+    pub fn parse_keyer_speed(frame: &str) -> Option<u32> {
+        Self::parse_code(frame, "KS", 3)
+    }
+
+    /// Move the clarifier from `from_hz` to `to_hz`. The TS-570 only steps
+    /// RIT, so this pulses up or down by the difference; `clear_rit` is the
+    /// only way back to a known zero.
+    pub async fn set_rit(serial: &SerialManager, from_hz: i32, to_hz: i32) -> Result<(), JsValue> {
+        for command in rit_commands(from_hz, to_hz) {
+            serial.write_command(command).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn set_rit_enabled(serial: &SerialManager, enabled: bool) -> Result<(), JsValue> {
+        serial.write_command(if enabled { "RT1;" } else { "RT0;" }).await
+    }
+
+    pub async fn clear_rit(serial: &SerialManager) -> Result<(), JsValue> {
+        serial.write_command("RC;").await
+    }
+
+    /// Start the internal antenna tuner. This keys the transmitter.
+    pub async fn start_atu(serial: &SerialManager) -> Result<(), JsValue> {
+        serial.write_command("AC111;").await
+    }
+
+    /// Read the tuner state; `Err` on timeout, which usually means the
+    /// radio has no internal tuner.
+    pub async fn query_atu(serial: &SerialManager) -> Result<String, JsValue> {
+        serial.query("AC;", "AC", DEFAULT_FRAME_TIMEOUT_MS).await
+    }
+
+    /// Whether an `ACabc;` frame reports a tune in progress (`c` = 1).
+    pub fn atu_tuning(frame: &str) -> Option<bool> {
+        let frame = frame.trim();
+        let body = frame.strip_suffix(';').unwrap_or(frame);
+        let digits = body.strip_prefix("AC")?;
+        if digits.len() != 3 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        Some(digits.ends_with('1'))
+    }
+
+    /// Set the receive bandwidth nearest `width_hz` for the radio's current
+    /// mode, returning the width applied.
+    pub async fn set_filter_width(serial: &SerialManager, width_hz: u32) -> Result<u32, JsValue> {
+        let mode = Self::query_mode(serial).await?;
+        let code = Self::parse_mode(&mode).ok_or_else(|| JsValue::from_str("unrecognized mode reply"))?;
+        let (commands, width) = filter_width_commands(code, width_hz).ok_or_else(|| {
+            JsValue::from_str(&format!(
+                "no adjustable width in {}",
+                Self::mode_label(code).unwrap_or("this mode")
+            ))
+        })?;
+        serial.send_sequence(&commands).await?;
+        Ok(width)
+    }
+
+    /// Read the receive bandwidth for the radio's current mode, in Hz.
+    pub async fn query_filter_width(serial: &SerialManager) -> Result<u32, JsValue> {
+        let mode = Self::query_mode(serial).await?;
+        let unreadable = || JsValue::from_str("unrecognized filter reply");
+        match Self::parse_mode(&mode) {
+            Some(3 | 6 | 7 | 9) => {
+                let frame = serial.query("FW;", "FW", DEFAULT_FRAME_TIMEOUT_MS).await?;
+                Self::parse_code(&frame, "FW", 4).ok_or_else(unreadable)
+            }
+            Some(1 | 2) => {
+                let low = serial.query("SL;", "SL", DEFAULT_FRAME_TIMEOUT_MS).await?;
+                let high = serial.query("SH;", "SH", DEFAULT_FRAME_TIMEOUT_MS).await?;
+                let low = Self::parse_code(&low, "SL", 2).and_then(|c| SSB_LOW_CUTS_HZ.get(c as usize));
+                let high = Self::parse_code(&high, "SH", 2).and_then(|c| SSB_HIGH_CUTS_HZ.get(c as usize));
+                match (low, high) {
+                    (Some(low), Some(high)) => Ok(high.saturating_sub(*low)),
+                    _ => Err(unreadable()),
+                }
+            }
+            _ => Err(JsValue::from_str("no adjustable width in this mode")),
+        }
+    }
+
+    /// Parse a fixed-width numeric reply such as `FW0500;` or `SH05;`.
+    fn parse_code(frame: &str, prefix: &str, digits: usize) -> Option<u32> {
+        let frame = frame.trim();
+        let body = frame.strip_suffix(';').unwrap_or(frame);
+        let value = body.strip_prefix(prefix)?;
+        if value.len() != digits || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        value.parse::<u32>().ok()
+    }
+
+    pub async fn set_noise_blanker(serial: &SerialManager, on: bool) -> Result<(), JsValue> {
+        serial.write_command(if on { "NB1;" } else { "NB0;" }).await
+    }
+
+    /// Noise blanker state from `NB;`.
+    pub async fn query_noise_blanker(serial: &SerialManager) -> Result<bool, JsValue> {
+        let frame = serial.query("NB;", "NB", DEFAULT_FRAME_TIMEOUT_MS).await?;
+        Self::parse_code(&frame, "NB", 1)
+            .map(|v| v == 1)
+            .ok_or_else(|| JsValue::from_str("unrecognized NB reply"))
+    }
+
+    /// Set DSP noise reduction: 0 off, 1 NR1, 2 NR2.
+    pub async fn set_noise_reduction(serial: &SerialManager, level: u8) -> Result<(), JsValue> {
+        serial.write_command(&format!("NR{};", level.min(2))).await
+    }
+
+    pub async fn query_noise_reduction(serial: &SerialManager) -> Result<u8, JsValue> {
+        let frame = serial.query("NR;", "NR", DEFAULT_FRAME_TIMEOUT_MS).await?;
+        Self::parse_code(&frame, "NR", 1)
+            .filter(|v| *v <= 2)
+            .map(|v| v as u8)
+            .ok_or_else(|| JsValue::from_str("unrecognized NR reply"))
+    }
+
+    pub async fn set_agc(serial: &SerialManager, agc: AgcSpeed) -> Result<(), JsValue> {
+        serial
+            .write_command(&format!("GC{};", serial.kenwood_model().agc_code(agc)))
+            .await
+    }
+
+    /// AGC time constant from `GC;`.
+    pub async fn query_agc(serial: &SerialManager) -> Result<AgcSpeed, JsValue> {
+        let frame = serial.query("GC;", "GC", DEFAULT_FRAME_TIMEOUT_MS).await?;
+        Self::parse_agc(&frame, serial.kenwood_model()).ok_or_else(|| JsValue::from_str("unrecognized GC reply"))
+    }
+
+    pub fn parse_agc(frame: &str, model: KenwoodModel) -> Option<AgcSpeed> {
+        Self::parse_code(frame, "GC", 1).and_then(|code| model.agc_from_code(code))
+    }
+
+    /// Whether the radio is transmitting, from the `IF;` status frame.
+    pub async fn query_transmitting(serial: &SerialManager) -> Result<bool, JsValue> {
+        let frame = serial.query("IF;", "IF", DEFAULT_FRAME_TIMEOUT_MS).await?;
+        Self::parse_transmitting(&frame).ok_or_else(|| JsValue::from_str("unrecognized IF reply"))
+    }
+
+    /// Read the TX/RX flag from an `IF` frame: after `IF` come the 11-digit
+    /// frequency, 5-character step, 5-character RIT offset, RIT, XIT,
+    /// memory bank and 2-digit channel, then `0` for RX or `1` for TX.
+    /// The same frame arrives unprompted in `AI2;` auto-information mode.
+    pub fn parse_transmitting(frame: &str) -> Option<bool> {
+        const TX_FLAG: usize = 2 + 11 + 5 + 5 + 1 + 1 + 1 + 2;
+        let frame = frame.trim();
+        if !frame.starts_with("IF") {
+            return None;
+        }
+        match frame.as_bytes().get(TX_FLAG)? {
+            b'0' => Some(false),
+            b'1' => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Switch the radio on from standby. A sleeping radio can drop the
+    /// first command while it wakes, so `PS1;` is sent twice.
+    pub async fn power_on(serial: &SerialManager) -> Result<(), JsValue> {
+        serial.write_command("PS1;").await?;
+        TimeoutFuture::new(POWER_ON_WAKE_MS).await;
+        serial.write_command("PS1;").await
+    }
+
+    pub async fn power_off(serial: &SerialManager) -> Result<(), JsValue> {
+        serial.write_command("PS0;").await
+    }
+
+    /// Whether the radio is on, from `PS;`. A radio in standby with its
+    /// CAT port asleep doesn't answer at all, so a timeout here usually
+    /// means "off" rather than a broken link.
+    pub async fn query_power_state(serial: &SerialManager, timeout_ms: u32) -> Result<bool, JsValue> {
+        let frame = serial.query("PS;", "PS", timeout_ms).await?;
+        Self::parse_power_state(&frame).ok_or_else(|| JsValue::from_str("unrecognized PS reply"))
+    }
+
+    pub fn parse_power_state(frame: &str) -> Option<bool> {
+        match Self::parse_code(frame, "PS", 1)? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Read-only link check: ask the radio to identify itself. Nothing is
+    /// changed and the transmitter is never keyed.
+    pub async fn ping(serial: &SerialManager) -> Result<String, JsValue> {
+        serial.query("ID;", "ID", DEFAULT_FRAME_TIMEOUT_MS).await
+    }
+
+    /// Model name for an `IDnnn;` reply, where known.
+    pub fn model_name(frame: &str) -> Option<&'static str> {
+        match Self::parse_code(frame, "ID", 3)? {
+            15 => Some("TS-870"),
+            17 => Some("TS-570D"),
+            18 => Some("TS-570S"),
+            19 => Some("TS-2000"),
+            20 => Some("TS-480"),
+            21 => Some("TS-590S"),
+            22 => Some("TS-990"),
+            23 => Some("TS-590SG"),
+            24 => Some("TS-890"),
+            _ => None,
+        }
+    }
+
+    pub async fn query_power(serial: &SerialManager) -> Result<String, JsValue> {
+        serial.query("PC;", "PC", DEFAULT_FRAME_TIMEOUT_MS).await
+    }
+
+    /// Parse a `PCnnn;` power frame into watts.
+    pub fn parse_power(frame: &str) -> Option<u32> {
+        let frame = frame.trim();
+        let body = frame.strip_suffix(';').unwrap_or(frame);
+        let digits = body.strip_prefix("PC")?;
+        if digits.len() != 3 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse::<u32>().ok()
+    }
+
+    /// Parse an `FA`/`FB` VFO frequency frame such as `FA00014062000;`
+    /// into Hz. Returns `None` for anything that isn't a well-formed
+    /// 11-digit frequency answer.
+    pub fn parse_frequency(frame: &str) -> Option<u64> {
+        let frame = frame.trim();
+        let body = frame.strip_suffix(';').unwrap_or(frame);
+        let digits = body
+            .strip_prefix("FA")
+            .or_else(|| body.strip_prefix("FB"))?;
+        if digits.len() != 11 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse::<u64>().ok()
+    }
+}
+
+impl RigDriver for KenwoodDriver {
+    fn tune<'a>(&'a self, serial: &'a SerialManager, freq_hz: u64, mode: &'a str) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        KenwoodDriver::tune(serial, freq_hz, mode).boxed_local()
+    }
+
+    fn set_mode<'a>(&'a self, serial: &'a SerialManager, mode: &'a str) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        KenwoodDriver::set_mode(serial, mode).boxed_local()
+    }
+
+    fn set_vfo_a<'a>(&'a self, serial: &'a SerialManager) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        KenwoodDriver::set_vfo_a(serial).boxed_local()
+    }
+
+    fn set_vfo_b<'a>(&'a self, serial: &'a SerialManager) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        KenwoodDriver::set_vfo_b(serial).boxed_local()
+    }
+
+    fn query_frequency<'a>(&'a self, serial: &'a SerialManager) -> LocalBoxFuture<'a, Result<String, JsValue>> {
+        KenwoodDriver::query_frequency(serial).boxed_local()
+    }
+
+    fn send_raw<'a>(&'a self, serial: &'a SerialManager, cmd: &'a str) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        KenwoodDriver::send_raw(serial, cmd).boxed_local()
+    }
+
+    fn parse_frequency(&self, frame: &str) -> Option<u64> {
+        KenwoodDriver::parse_frequency(frame)
+    }
+}
+
+/// CAT driver for Yaesu's newer Kenwood-like protocol (FT-891, FT-991/A).
+///
+/// Differences from the Kenwood driver:
+/// - `FA`/`FB` carry a 9-digit Hz field instead of 11 (`FA014062000;`).
+/// - `MD` takes a receiver selector and a single hex-style code, e.g.
+///   `MD03;` for CW. The codes differ from Kenwood's: 1=LSB, 2=USB, 3=CW-U,
+///   4=FM, 5=AM, C=DATA-USB.
+/// - VFO selection uses `VS0;`/`VS1;` rather than `FR`/`FT`.
+pub struct YaesuDriver;
+
+impl YaesuDriver {
+    /// Map a spot mode onto the Yaesu `MD0x` command. Digital modes use
+    /// DATA-USB so the rig's data input is keyed rather than the mic.
+    fn mode_command(mode: &str) -> &'static str {
+        match mode.to_uppercase().as_str() {
+            "LSB" => "MD01;",
+            "USB" | "SSB" => "MD02;",
+            "CW" => "MD03;",
+            "FM" => "MD04;",
+            "AM" => "MD05;",
+            "FT8" | "FT4" | "PSK31" | "RTTY" | "DATA" => "MD0C;",
+            _ => "MD02;",
+        }
+    }
+
+    pub async fn tune(serial: &SerialManager, freq_hz: u64, mode: &str) -> Result<(), JsValue> {
+        Self::set_vfo_a(serial).await?;
+        TimeoutFuture::new(serial.command_delay()).await;
+        serial.write_command(&format!("FA{:09};", freq_hz)).await?;
+        TimeoutFuture::new(serial.command_delay()).await;
+        serial.write_command(Self::mode_command(mode)).await
+    }
+
+    pub async fn set_vfo_a(serial: &SerialManager) -> Result<(), JsValue> {
+        serial.write_command("VS0;").await
+    }
+
+    pub async fn set_vfo_b(serial: &SerialManager) -> Result<(), JsValue> {
+        serial.write_command("VS1;").await
+    }
+
+    pub async fn set_mode(serial: &SerialManager, mode: &str) -> Result<(), JsValue> {
+        serial.write_command(Self::mode_command(mode)).await
+    }
+
+    pub async fn send_raw(serial: &SerialManager, cmd: &str) -> Result<(), JsValue> {
+        serial.send_raw(cmd).await
     }
 
     pub async fn query_frequency(serial: &SerialManager) -> Result<String, JsValue> {
-        // Query current VFO A frequency; response should be read from the
-        // persistent streaming reader (if present). We still send the query
-        // command here and return the next available chunk from the reader.
-        serial.write_command("FA;").await?;
-        let resp = serial.read_from_persistent_reader().await?;
-        Ok(resp)
+        serial.query("FA;", "FA", DEFAULT_FRAME_TIMEOUT_MS).await
+    }
+
+    /// Parse a 9-digit `FA`/`FB` answer such as `FA014062000;` into Hz.
+    pub fn parse_frequency(frame: &str) -> Option<u64> {
+        let frame = frame.trim();
+        let body = frame.strip_suffix(';').unwrap_or(frame);
+        let digits = body
+            .strip_prefix("FA")
+            .or_else(|| body.strip_prefix("FB"))?;
+        if digits.len() != 9 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse::<u64>().ok()
+    }
+}
+
+impl RigDriver for YaesuDriver {
+    fn tune<'a>(&'a self, serial: &'a SerialManager, freq_hz: u64, mode: &'a str) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        YaesuDriver::tune(serial, freq_hz, mode).boxed_local()
+    }
+
+    fn set_mode<'a>(&'a self, serial: &'a SerialManager, mode: &'a str) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        YaesuDriver::set_mode(serial, mode).boxed_local()
+    }
+
+    fn set_vfo_a<'a>(&'a self, serial: &'a SerialManager) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        YaesuDriver::set_vfo_a(serial).boxed_local()
+    }
+
+    fn set_vfo_b<'a>(&'a self, serial: &'a SerialManager) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        YaesuDriver::set_vfo_b(serial).boxed_local()
+    }
+
+    fn query_frequency<'a>(&'a self, serial: &'a SerialManager) -> LocalBoxFuture<'a, Result<String, JsValue>> {
+        YaesuDriver::query_frequency(serial).boxed_local()
+    }
+
+    fn send_raw<'a>(&'a self, serial: &'a SerialManager, cmd: &'a str) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        YaesuDriver::send_raw(serial, cmd).boxed_local()
+    }
+
+    fn parse_frequency(&self, frame: &str) -> Option<u64> {
+        YaesuDriver::parse_frequency(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tune_14062_cw_builds_kenwood_commands() {
+        let hz = (14.062_f64 * 1_000_000.0).round() as u64;
+        assert_eq!(frequency_command(hz), "FA00014062000;");
+        assert_eq!(mode_command("CW"), "MD3;");
+    }
+
+    #[test]
+    fn usb_label_names_known_chips() {
+        assert_eq!(usb_device_label(0x0403, 0x6001), "FTDI FT232R (0403:6001)");
+        assert_eq!(usb_device_label(0x0403, 0x6014), "FTDI (0403:6014)");
+        assert_eq!(usb_device_label(0x1234, 0x5678), "USB 1234:5678");
+    }
+
+    #[test]
+    fn waiter_for_prefers_a_matching_query() {
+        let waiter = |prefix: &str| FrameWaiter { prefix: prefix.to_string(), reply: oneshot::channel().0 };
+        let waiters = [waiter(""), waiter("IF"), waiter("FA")];
+        assert_eq!(waiter_for(&waiters, "IF00014062000;"), Some(1));
+        assert_eq!(waiter_for(&waiters, "FA00014062000;"), Some(2));
+        assert_eq!(waiter_for(&waiters, "MD3;"), Some(0));
+        assert_eq!(waiter_for(&waiters[1..], "MD3;"), None);
+    }
+
+    #[test]
+    fn split_frames_leaves_partial_frame() {
+        let mut buf = b"AI2;\r\nFA00014062000;MD3;FB0001".to_vec();
+        assert_eq!(split_frames(&mut buf), vec!["AI2;", "FA00014062000;", "MD3;"]);
+        assert_eq!(buf, b"FB0001");
+        assert!(split_frames(&mut buf).is_empty());
+    }
+
+    #[test]
+    fn split_frames_reassembles_multibyte_characters() {
+        let text = "NA°;".as_bytes();
+        let mut buf = text[..3].to_vec();
+        assert!(split_frames(&mut buf).is_empty());
+        buf.extend_from_slice(&text[3..]);
+        assert_eq!(split_frames(&mut buf), vec!["NA°;"]);
+    }
+
+    #[test]
+    fn parse_cat_sequence_splits_and_validates() {
+        assert_eq!(
+            parse_cat_sequence("FA00014062000; md3;FW0200;").unwrap(),
+            vec!["FA00014062000;", "MD3;", "FW0200;"]
+        );
+        assert!(parse_cat_sequence("FA14062;1X;").is_err());
+        assert!(parse_cat_sequence(" ; ").is_err());
+    }
+
+    #[test]
+    fn filter_width_follows_mode() {
+        assert_eq!(filter_width_commands(3, 500), Some((vec!["FW0500;".to_string()], 500)));
+        assert_eq!(filter_width_commands(7, 250).map(|(_, w)| w), Some(200));
+        assert_eq!(
+            filter_width_commands(2, 2400),
+            Some((vec!["SL03;".to_string(), "SH06;".to_string()], 2400))
+        );
+        assert_eq!(filter_width_commands(4, 500), None);
+    }
+
+    #[test]
+    fn model_name_from_id_reply() {
+        assert_eq!(KenwoodDriver::model_name("ID017;"), Some("TS-570D"));
+        assert_eq!(KenwoodDriver::model_name("ID018;"), Some("TS-570S"));
+        assert_eq!(KenwoodDriver::model_name("ID999;"), None);
+        assert_eq!(KenwoodDriver::model_name("FA00014062000;"), None);
+    }
+
+    #[test]
+    fn parse_keyer_speed_reads_wpm() {
+        assert_eq!(KenwoodDriver::parse_keyer_speed("KS025;"), Some(25));
+        assert_eq!(KenwoodDriver::parse_keyer_speed("KS25;"), None);
+    }
+
+    #[test]
+    fn parse_agc_accepts_known_codes() {
+        let ts570 = KenwoodModel::Ts570;
+        assert_eq!(KenwoodDriver::parse_agc("GC1;", ts570), Some(AgcSpeed::Fast));
+        assert_eq!(KenwoodDriver::parse_agc("GC0;", ts570), Some(AgcSpeed::Off));
+        assert_eq!(KenwoodDriver::parse_agc("GC3;", ts570), None);
+        assert_eq!(KenwoodDriver::parse_agc("GC;", ts570), None);
+        assert_eq!(KenwoodDriver::parse_agc("GC1;", KenwoodModel::Ts590), Some(AgcSpeed::Slow));
+    }
+
+    #[test]
+    fn parse_transmitting_reads_if_flag() {
+        let rx = "IF00014062000     +00000000003000000 ;";
+        let tx = "IF00014062000     +00000000013000000 ;";
+        assert_eq!(KenwoodDriver::parse_transmitting(rx), Some(false));
+        assert_eq!(KenwoodDriver::parse_transmitting(tx), Some(true));
+        assert_eq!(KenwoodDriver::parse_transmitting("IF000;"), None);
+        assert_eq!(KenwoodDriver::parse_transmitting("FA00014062000;"), None);
+    }
+
+    #[test]
+    fn parse_power_state_reads_ps_flag() {
+        assert_eq!(KenwoodDriver::parse_power_state("PS1;"), Some(true));
+        assert_eq!(KenwoodDriver::parse_power_state("PS0;"), Some(false));
+        assert_eq!(KenwoodDriver::parse_power_state("PS9;"), None);
+        assert_eq!(KenwoodDriver::parse_power_state("PC050;"), None);
+    }
+
+    #[test]
+    fn parse_frequency_reads_fa_and_fb_frames() {
+        assert_eq!(KenwoodDriver::parse_frequency("FA00014062000;"), Some(14_062_000));
+        assert_eq!(KenwoodDriver::parse_frequency("FB00007032500;"), Some(7_032_500));
+        assert_eq!(KenwoodDriver::parse_frequency("FA00000000000;"), Some(0));
+        assert_eq!(KenwoodDriver::parse_frequency("FA0001406200;"), None);
+        assert_eq!(KenwoodDriver::parse_frequency("FA0001406200X;"), None);
+        assert_eq!(KenwoodDriver::parse_frequency("FC00014062000;"), None);
+    }
+
+    #[test]
+    fn kenwood_models_differ_in_data_mode() {
+        assert_eq!(KenwoodModel::Ts570.mode_commands("FT8"), vec!["MD2;"]);
+        assert_eq!(KenwoodModel::Ts590.mode_commands("FT8"), vec!["MD2;", "DA1;"]);
+        assert_eq!(KenwoodModel::Ts890.mode_commands("USB"), vec!["MD2;", "DA0;"]);
+        assert_eq!(KenwoodModel::Ts890.mode_commands("CW"), vec!["MD3;"]);
+        assert_eq!(KenwoodModel::from_key("ts590"), Some(KenwoodModel::Ts590));
+    }
+
+    #[test]
+    fn may_transmit_flags_keying_commands() {
+        assert!(may_transmit("TX;"));
+        assert!(may_transmit("FA00014062000;tx;"));
+        assert!(may_transmit("KY CQ SOTA;"));
+        assert!(may_transmit("AC111;"));
+        assert!(!may_transmit("AC110;"));
+        assert!(!may_transmit("AC;"));
+        assert!(!may_transmit("KY;"));
+        assert!(!may_transmit("FA00014062000;MD3;"));
+        assert!(!may_transmit("RX;"));
+    }
+
+    #[test]
+    fn normalize_raw_command_terminates_and_rejects() {
+        assert_eq!(normalize_raw_command(" FA; "), Ok("FA;".to_string()));
+        assert_eq!(normalize_raw_command("MD3"), Ok("MD3;".to_string()));
+        assert!(normalize_raw_command("  ").is_err());
+        assert!(normalize_raw_command("FA°;").is_err());
+        assert!(normalize_raw_command("FA\t;").is_err());
+    }
+
+    #[test]
+    fn rit_commands_pulse_to_offset() {
+        assert_eq!(rit_commands(0, 30), vec!["RU;"; 3]);
+        assert_eq!(rit_commands(0, -25), vec!["RD;"; 3]);
+        assert!(rit_commands(0, 0).is_empty());
+        assert_eq!(rit_commands(0, 20_000).len(), (RIT_MAX_HZ / RIT_STEP_HZ) as usize);
+        assert_eq!(rit_commands(500, 550), vec!["RU;"; 5]);
+        assert_eq!(rit_commands(500, 450), vec!["RD;"; 5]);
+        assert!(rit_commands(RIT_MAX_HZ, RIT_MAX_HZ + 50).is_empty());
+    }
+
+    #[test]
+    fn mode_frames_map_to_labels() {
+        let label = |frame| KenwoodDriver::parse_mode(frame).and_then(KenwoodDriver::mode_label);
+        assert_eq!(label("MD3;"), Some("CW"));
+        assert_eq!(label("MD6;"), Some("FSK"));
+        assert_eq!(label("MD7;"), Some("CW-R"));
+        assert_eq!(label("MD0;"), None);
+        assert_eq!(label("FA00014062000;"), None);
+    }
+
+    #[test]
+    fn split_tx_goes_to_vfo_b() {
+        assert_eq!(vfo_b_frequency_command(14_063_000), "FB00014063000;");
+    }
+
+    #[test]
+    fn mode_command_maps_spot_modes() {
+        assert_eq!(mode_command("lsb"), "MD1;");
+        assert_eq!(mode_command("SSB"), "MD2;");
+        assert_eq!(mode_command("FT8"), "MD2;");
+        assert_eq!(mode_command("AM"), "MD5;");
     }
 }
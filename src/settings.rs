@@ -0,0 +1,168 @@
+use serde_json::{Map, Value};
+use std::fmt::Display;
+use std::str::FromStr;
+use web_sys::Storage;
+
+/// Every key the app keeps in localStorage starts with this.
+const PREFIX: &str = "sotachaser.";
+/// Written and removed again by `available`; never a real setting.
+const PROBE: &str = "sotachaser.storage_probe";
+
+/// The shape a stored value must have. Import skips values that don't fit
+/// so a hand-edited file can't leave the app parsing garbage on start-up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Bool,
+    Number,
+    Text,
+    Json,
+}
+
+impl Kind {
+    fn accepts(self, value: &str) -> bool {
+        match self {
+            Kind::Bool => value == "true" || value == "false",
+            Kind::Number => value.parse::<f64>().is_ok_and(f64::is_finite),
+            Kind::Text => true,
+            Kind::Json => serde_json::from_str::<Value>(value).is_ok(),
+        }
+    }
+}
+
+fn storage() -> Option<Storage> {
+    web_sys::window().and_then(|w| w.local_storage().ok().flatten())
+}
+
+/// Whether settings can actually be saved. Private browsing or blocked
+/// cookies can hide localStorage entirely or make every write throw, both
+/// of which otherwise look like "nothing saved yet".
+pub fn available() -> bool {
+    storage()
+        .filter(|storage| storage.set_item(PROBE, "1").is_ok())
+        .map(|storage| storage.remove_item(PROBE))
+        .is_some()
+}
+
+/// The stored value for `key`, or `None` if it is missing or doesn't parse.
+pub fn get<T: FromStr>(key: &str) -> Option<T> {
+    storage()?.get_item(key).ok().flatten()?.parse().ok()
+}
+
+pub fn get_or<T: FromStr>(key: &str, default_value: T) -> T {
+    get(key).unwrap_or(default_value)
+}
+
+pub fn set(key: &str, value: impl Display) {
+    if let Some(storage) = storage() {
+        let _ = storage.set_item(key, &value.to_string());
+    }
+}
+
+pub fn remove(key: &str) {
+    if let Some(storage) = storage() {
+        let _ = storage.remove_item(key);
+    }
+}
+
+/// Every stored `sotachaser.` key as a JSON object of strings.
+pub fn export_json() -> String {
+    let mut map = Map::new();
+    if let Some(storage) = storage() {
+        for index in 0..storage.length().unwrap_or(0) {
+            let Some(key) = storage.key(index).ok().flatten() else {
+                continue;
+            };
+            if !key.starts_with(PREFIX) || key == PROBE {
+                continue;
+            }
+            if let Ok(Some(value)) = storage.get_item(&key) {
+                map.insert(key, Value::String(value));
+            }
+        }
+    }
+    serde_json::to_string_pretty(&Value::Object(map)).unwrap_or_default()
+}
+
+/// The entries of an exported settings file that `schema` knows about.
+/// Unknown keys and values of the wrong kind are dropped; only a file that
+/// isn't a JSON object at all is an error.
+pub fn parse_import(json: &str, schema: &[(&str, Kind)]) -> Result<Vec<(String, String)>, String> {
+    let value: Value = serde_json::from_str(json).map_err(|e| format!("not valid JSON: {}", e))?;
+    let Value::Object(map) = value else {
+        return Err("expected a JSON object of settings".to_string());
+    };
+    Ok(map
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let (_, kind) = schema.iter().find(|(known, _)| *known == key)?;
+            let value = match value {
+                Value::String(text) => text,
+                Value::Bool(flag) => flag.to_string(),
+                Value::Number(number) => number.to_string(),
+                _ => return None,
+            };
+            kind.accepts(&value).then_some((key, value))
+        })
+        .collect())
+}
+
+/// Write the known settings from an exported file back to storage and
+/// return how many were applied.
+pub fn import_json(json: &str, schema: &[(&str, Kind)]) -> Result<usize, String> {
+    let entries = parse_import(json, schema)?;
+    let storage = storage().ok_or("settings storage is unavailable")?;
+    for (key, value) in &entries {
+        storage
+            .set_item(key, value)
+            .map_err(|_| format!("could not save {}", key))?;
+    }
+    Ok(entries.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &[(&str, Kind)] = &[
+        ("sotachaser.split_enabled", Kind::Bool),
+        ("sotachaser.refresh_secs", Kind::Number),
+        ("sotachaser.band", Kind::Text),
+        ("sotachaser.favorites", Kind::Json),
+    ];
+
+    #[test]
+    fn import_keeps_known_valid_settings() {
+        let json = r#"{
+            "sotachaser.split_enabled": "true",
+            "sotachaser.refresh_secs": 120,
+            "sotachaser.band": "20m",
+            "sotachaser.favorites": "[\"W7W/LC-001\"]",
+            "sotachaser.unknown": "x",
+            "other.key": "y"
+        }"#;
+        let mut entries = parse_import(json, SCHEMA).unwrap();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![
+                ("sotachaser.band".to_string(), "20m".to_string()),
+                ("sotachaser.favorites".to_string(), "[\"W7W/LC-001\"]".to_string()),
+                ("sotachaser.refresh_secs".to_string(), "120".to_string()),
+                ("sotachaser.split_enabled".to_string(), "true".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn import_drops_invalid_values() {
+        let json = r#"{
+            "sotachaser.split_enabled": "yes",
+            "sotachaser.refresh_secs": "soon",
+            "sotachaser.favorites": "[unclosed",
+            "sotachaser.band": ["20m"]
+        }"#;
+        assert!(parse_import(json, SCHEMA).unwrap().is_empty());
+        assert!(parse_import("[1, 2]", SCHEMA).is_err());
+        assert!(parse_import("not json", SCHEMA).is_err());
+    }
+}
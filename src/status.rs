@@ -0,0 +1,147 @@
+use gloo_timers::callback::Timeout;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use yew::prelude::*;
+
+/// How long an info or success toast stays up before fading.
+const INFO_TOAST_MS: u32 = 4_000;
+/// Warnings are for transient trouble, like a dropped connection.
+const WARNING_TOAST_MS: u32 = 8_000;
+/// Errors stay up longer so they aren't missed; they can also be dismissed.
+const ERROR_TOAST_MS: u32 = 12_000;
+/// Oldest toasts are dropped beyond this many.
+const MAX_TOASTS: usize = 5;
+
+static NEXT_STATUS_ID: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl StatusLevel {
+    fn class(self) -> &'static str {
+        match self {
+            StatusLevel::Info => "info",
+            StatusLevel::Success => "success",
+            StatusLevel::Warning => "warning",
+            StatusLevel::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusMessage {
+    id: u32,
+    level: StatusLevel,
+    text: String,
+    /// Local wall-clock time the message was raised, as HH:MM:SS.
+    time: String,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct StatusQueue {
+    messages: Vec<StatusMessage>,
+}
+
+pub enum StatusAction {
+    Push(StatusMessage),
+    Dismiss(u32),
+}
+
+impl Reducible for StatusQueue {
+    type Action = StatusAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut messages = self.messages.clone();
+        match action {
+            StatusAction::Push(message) => {
+                // A repeated message replaces the older copy instead of stacking.
+                messages.retain(|m| m.text != message.text);
+                messages.push(message);
+                if messages.len() > MAX_TOASTS {
+                    messages.drain(..messages.len() - MAX_TOASTS);
+                }
+            }
+            StatusAction::Dismiss(id) => messages.retain(|m| m.id != id),
+        }
+        Rc::new(StatusQueue { messages })
+    }
+}
+
+/// Cloneable handle for raising status toasts from callbacks and async
+/// tasks. Backed by a reducer so concurrent pushes never overwrite each
+/// other.
+#[derive(Clone, PartialEq)]
+pub struct StatusHandle(UseReducerHandle<StatusQueue>);
+
+impl StatusHandle {
+    /// Raise a toast styled by `level`. Empty messages are ignored.
+    pub fn push_status(&self, level: StatusLevel, text: impl Into<String>) {
+        let text = text.into();
+        if text.is_empty() {
+            return;
+        }
+        let id = NEXT_STATUS_ID.fetch_add(1, Ordering::Relaxed);
+        let now = js_sys::Date::new_0();
+        let time = format!(
+            "{:02}:{:02}:{:02}",
+            now.get_hours(),
+            now.get_minutes(),
+            now.get_seconds()
+        );
+        self.0.dispatch(StatusAction::Push(StatusMessage { id, level, text, time }));
+
+        let lifetime = match level {
+            StatusLevel::Info | StatusLevel::Success => INFO_TOAST_MS,
+            StatusLevel::Warning => WARNING_TOAST_MS,
+            StatusLevel::Error => ERROR_TOAST_MS,
+        };
+        let queue = self.0.clone();
+        Timeout::new(lifetime, move || queue.dispatch(StatusAction::Dismiss(id))).forget();
+    }
+
+    pub fn dismiss(&self, id: u32) {
+        self.0.dispatch(StatusAction::Dismiss(id));
+    }
+
+    pub fn messages(&self) -> &[StatusMessage] {
+        &self.0.messages
+    }
+}
+
+#[hook]
+pub fn use_status() -> StatusHandle {
+    StatusHandle(use_reducer(StatusQueue::default))
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ToastsProps {
+    pub status: StatusHandle,
+}
+
+/// Stack of transient status toasts, newest at the bottom.
+#[function_component(Toasts)]
+pub fn toasts(props: &ToastsProps) -> Html {
+    html! {
+        <div class="toasts" role="status" aria-live="polite">
+            { for props.status.messages().iter().map(|message| {
+                let on_dismiss = {
+                    let status = props.status.clone();
+                    let id = message.id;
+                    Callback::from(move |_| status.dismiss(id))
+                };
+                html! {
+                    <div class={classes!("toast", message.level.class())} key={message.id}>
+                        <span class="toast-time">{ message.time.clone() }</span>
+                        <span class="toast-text">{ message.text.clone() }</span>
+                        <button class="toast-dismiss" aria-label="Dismiss" onclick={on_dismiss}>{"×"}</button>
+                    </div>
+                }
+            }) }
+        </div>
+    }
+}